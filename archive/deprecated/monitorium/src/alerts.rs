@@ -0,0 +1,306 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use anyhow::{anyhow, Context, Result};
+use crate::config::{AlertRuleConfig, AlertScope};
+use crate::mock_data::{NodeMetrics, ServiceMetrics};
+
+/// State of one rule for one node or service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertState {
+    Ok,
+    /// Condition holds but not yet for the rule's `for` duration
+    Pending,
+    Firing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn parse(op: &str) -> Option<Self> {
+        match op {
+            ">" => Some(Self::Greater),
+            ">=" => Some(Self::GreaterOrEqual),
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessOrEqual),
+            "==" => Some(Self::Equal),
+            "!=" => Some(Self::NotEqual),
+            _ => None,
+        }
+    }
+
+    fn holds(self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Greater => value > threshold,
+            Self::GreaterOrEqual => value >= threshold,
+            Self::Less => value < threshold,
+            Self::LessOrEqual => value <= threshold,
+            Self::Equal => value == threshold,
+            Self::NotEqual => value != threshold,
+        }
+    }
+}
+
+/// A parsed rule such as `cpu_usage > 90 for 2m`
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub scope: AlertScope,
+    /// Only evaluate against this node/service (all of them when None)
+    pub target: Option<String>,
+    pub metric: String,
+    comparison: Comparison,
+    threshold: f64,
+    pub hold_for: Duration,
+}
+
+impl AlertRule {
+    pub fn parse(config: &AlertRuleConfig) -> Result<Self> {
+        let tokens: Vec<&str> = config.rule.split_whitespace().collect();
+        let (condition, hold_for) = match tokens.as_slice() {
+            [metric, op, threshold] => ([*metric, *op, *threshold], Duration::ZERO),
+            [metric, op, threshold, "for", duration] => {
+                let hold_for = crate::parse_duration(duration).map_err(|e| anyhow!(e))?;
+                ([*metric, *op, *threshold], hold_for)
+            }
+            _ => return Err(anyhow!("expected '<metric> <op> <value> [for <duration>]'")),
+        };
+        let [metric, op, threshold] = condition;
+
+        if !is_known_metric(config.scope, metric) {
+            return Err(anyhow!("unknown {:?} metric '{}'", config.scope, metric));
+        }
+        let comparison = Comparison::parse(op)
+            .with_context(|| format!("unknown comparison '{}' (use >, >=, <, <=, == or !=)", op))?;
+        let threshold = threshold
+            .parse()
+            .with_context(|| format!("invalid threshold '{}'", threshold))?;
+
+        Ok(Self {
+            name: config.name.clone(),
+            scope: config.scope,
+            target: config.target.clone(),
+            metric: metric.to_string(),
+            comparison,
+            threshold,
+            hold_for,
+        })
+    }
+
+    /// State given timestamped samples, oldest first. The rule fires once the
+    /// condition has held for every sample over at least `hold_for`.
+    pub fn evaluate(&self, history: &[(Instant, f64)], now: Instant) -> AlertState {
+        let holding_since = history
+            .iter()
+            .rev()
+            .take_while(|(_, value)| self.comparison.holds(*value, self.threshold))
+            .last()
+            .map(|(at, _)| *at);
+
+        match holding_since {
+            None => AlertState::Ok,
+            Some(since) if now.duration_since(since) >= self.hold_for => AlertState::Firing,
+            Some(_) => AlertState::Pending,
+        }
+    }
+}
+
+/// A rule's current state for one node or service
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub rule: String,
+    pub target: String,
+    pub state: AlertState,
+    pub value: f64,
+}
+
+/// Evaluates the configured rules each cycle, keeping the samples they need
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    samples: HashMap<(usize, String), VecDeque<(Instant, f64)>>,
+    pub alerts: Vec<Alert>,
+}
+
+impl AlertEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    pub fn has_rules(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    /// Record the current values and re-evaluate every rule, returning how
+    /// many alerts started firing
+    pub fn evaluate(&mut self, nodes: &HashMap<String, NodeMetrics>, services: &HashMap<String, ServiceMetrics>) -> usize {
+        let now = Instant::now();
+        let mut alerts = Vec::new();
+
+        for (index, rule) in self.rules.iter().enumerate() {
+            let targets: Vec<(&String, f64)> = match rule.scope {
+                AlertScope::Node => nodes
+                    .iter()
+                    .filter_map(|(name, node)| Some((name, node_metric(node, &rule.metric)?)))
+                    .collect(),
+                AlertScope::Service => services
+                    .iter()
+                    .filter_map(|(name, service)| Some((name, service_metric(service, &rule.metric)?)))
+                    .collect(),
+            };
+
+            for (target, value) in targets {
+                if rule.target.as_ref().is_some_and(|wanted| wanted != target) {
+                    continue;
+                }
+
+                let samples = self.samples.entry((index, target.clone())).or_default();
+                samples.push_back((now, value));
+                // Keep one sample older than the window so a full hold can be seen
+                while samples.len() > 2 && now.duration_since(samples[1].0) > rule.hold_for {
+                    samples.pop_front();
+                }
+
+                alerts.push(Alert {
+                    rule: rule.name.clone(),
+                    target: target.clone(),
+                    state: rule.evaluate(samples.make_contiguous(), now),
+                    value,
+                });
+            }
+        }
+
+        let was_firing = |alert: &Alert| {
+            self.alerts.iter().any(|old| old.state == AlertState::Firing && old.rule == alert.rule && old.target == alert.target)
+        };
+        let newly_firing = alerts.iter().filter(|alert| alert.state == AlertState::Firing && !was_firing(alert)).count();
+
+        // Firing first, then pending, then by name
+        alerts.sort_by(|a, b| b.state.cmp(&a.state).then_with(|| a.target.cmp(&b.target)).then_with(|| a.rule.cmp(&b.rule)));
+        self.alerts = alerts;
+        newly_firing
+    }
+}
+
+fn is_known_metric(scope: AlertScope, metric: &str) -> bool {
+    match scope {
+        AlertScope::Node => NODE_METRICS.contains(&metric),
+        AlertScope::Service => SERVICE_METRICS.contains(&metric),
+    }
+}
+
+const NODE_METRICS: [&str; 7] = ["cpu_usage", "memory_usage", "swap_usage", "gpu_usage", "disk_usage", "temperature", "load1"];
+const SERVICE_METRICS: [&str; 6] = ["cpu_usage", "memory_usage", "requests_per_sec", "response_time", "error_rate", "cert_days"];
+
+fn node_metric(node: &NodeMetrics, metric: &str) -> Option<f64> {
+    match metric {
+        "cpu_usage" => Some(node.cpu_usage),
+        "memory_usage" => Some(node.memory_usage),
+        "swap_usage" => Some(node.swap_usage),
+        "gpu_usage" => Some(node.gpu_usage),
+        "disk_usage" => Some(node.disk_usage),
+        "temperature" => Some(node.temperature),
+        "load1" => Some(node.load_avg[0]),
+        _ => None,
+    }
+}
+
+fn service_metric(service: &ServiceMetrics, metric: &str) -> Option<f64> {
+    match metric {
+        "cpu_usage" => Some(service.cpu_usage),
+        "memory_usage" => Some(service.memory_usage),
+        "requests_per_sec" => Some(service.requests_per_sec),
+        "response_time" => Some(service.response_time),
+        "error_rate" => Some(service.error_rate),
+        "cert_days" => service.cert_expires_at.map(|expires_at| {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            (expires_at as f64 - now as f64) / 86_400.0
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(scope: AlertScope, rule: &str) -> Result<AlertRule> {
+        AlertRule::parse(&AlertRuleConfig {
+            name: "test".to_string(),
+            scope,
+            rule: rule.to_string(),
+            target: None,
+        })
+    }
+
+    #[test]
+    fn rules_parse_metric_comparison_and_hold() {
+        let parsed = rule(AlertScope::Node, "cpu_usage >= 90 for 2m").unwrap();
+        assert_eq!(parsed.metric, "cpu_usage");
+        assert_eq!(parsed.comparison, Comparison::GreaterOrEqual);
+        assert_eq!(parsed.threshold, 90.0);
+        assert_eq!(parsed.hold_for, Duration::from_secs(120));
+        assert_eq!(rule(AlertScope::Service, "error_rate > 1").unwrap().hold_for, Duration::ZERO);
+
+        let error = |scope, text| format!("{:#}", rule(scope, text).unwrap_err());
+        assert!(error(AlertScope::Node, "cpu_usage => 90").contains("unknown comparison '=>'"));
+        assert!(error(AlertScope::Node, "error_rate > 1").contains("unknown Node metric 'error_rate'"));
+        assert!(error(AlertScope::Node, "cpu_usage > hot").contains("invalid threshold"));
+        assert!(error(AlertScope::Node, "cpu_usage > 90 for 2 minutes").contains("expected"));
+        assert!(rule(AlertScope::Node, "cpu_usage > 90 for 2y").is_err());
+    }
+
+    #[test]
+    fn rule_goes_pending_then_firing_and_resets() {
+        let parsed = rule(AlertScope::Node, "temperature > 80 for 2m").unwrap();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let mut history = vec![(at(0), 70.0)];
+        assert_eq!(parsed.evaluate(&history, at(0)), AlertState::Ok);
+        history.push((at(60), 85.0));
+        assert_eq!(parsed.evaluate(&history, at(60)), AlertState::Pending);
+        history.push((at(180), 90.0));
+        assert_eq!(parsed.evaluate(&history, at(180)), AlertState::Firing);
+        history.push((at(240), 75.0));
+        assert_eq!(parsed.evaluate(&history, at(240)), AlertState::Ok);
+        history.push((at(300), 85.0));
+        assert_eq!(parsed.evaluate(&history, at(300)), AlertState::Pending);
+    }
+
+    #[test]
+    fn engine_counts_only_newly_firing_alerts() {
+        let (mut nodes, services) = crate::mock_data::generate_mock_metrics();
+        let mut engine = AlertEngine::new(vec![
+            rule(AlertScope::Node, "cpu_usage > 30").unwrap(),
+            rule(AlertScope::Service, "cpu_usage < 0").unwrap(),
+        ]);
+        let busy = nodes.values().filter(|node| node.cpu_usage > 30.0).count();
+        assert!(busy > 0 && busy < nodes.len());
+
+        assert_eq!(engine.evaluate(&nodes, &services), busy);
+        assert_eq!(engine.evaluate(&nodes, &services), 0);
+        assert_eq!(engine.alerts[0].state, AlertState::Firing);
+        assert_eq!(engine.alerts.len(), nodes.len() + services.len());
+
+        // A node that cools down and heats up again fires again
+        for node in nodes.values_mut() {
+            node.cpu_usage = 0.0;
+        }
+        assert_eq!(engine.evaluate(&nodes, &services), 0);
+        for node in nodes.values_mut() {
+            node.cpu_usage = 50.0;
+        }
+        assert_eq!(engine.evaluate(&nodes, &services), nodes.len());
+    }
+}
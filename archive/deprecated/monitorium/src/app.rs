@@ -1,24 +1,32 @@
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use anyhow::Context;
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
 use crate::mock_data::{NodeMetrics, ServiceMetrics};
 use crate::theme::{Theme, ThemeColors};
-use crate::prometheus_client::{PrometheusClient, PrometheusConfig};
-use crate::config::Config;
+use crate::prometheus_client::{PrometheusClient, QueryStats};
+use crate::alerts::{AlertEngine, AlertRule};
+use crate::baseline::Baseline;
+use crate::notes::Notes;
+use crate::scratchpad::Scratchpad;
+use crate::view_state::ViewState;
+use crate::config_watch::ConfigWatcher;
+use crate::health_checker::HealthResult;
+use crate::config::{AlertRuleConfig, AlertScope, BellMode, Config, ServiceHealthCheck, CurrentTab, NodeDefaults, NodeLayout, TotalFailurePolicy};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum CurrentTab {
-    Overview,
-    Nodes,
-    Services,
-    Compare,
-}
-
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ActivePanel {
     Nodes,
     Services,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilterState {
     pub enabled: bool,
     pub selected_node: Option<String>,
@@ -38,34 +46,396 @@ impl FilterState {
 }
 
 pub struct App {
-    pub title: String,
-    pub should_quit: bool,
     pub current_tab: CurrentTab,
     pub active_panel: ActivePanel,
     pub selected_node_index: usize,
     pub selected_service_index: usize,
-    pub selected_item_index: usize,
     pub filter: FilterState,
     pub selected_items: Vec<String>,
     pub tick_count: u64,
     pub current_theme: Theme,
     pub theme_colors: ThemeColors,
+    /// Themes use RGB colors rather than their ANSI fallback
+    pub truecolor: bool,
+    /// Period `ui.auto_theme` last switched to (true for light); a manual
+    /// theme change holds until the period changes again
+    pub auto_theme_light: Option<bool>,
+    pub display_mode: DisplayMode,
+    pub show_legend: bool,
+    pub show_queries: bool,
+    pub node_sort: NodeSortKey,
+    pub node_sort_desc: bool,
+    pub service_sort: ServiceSortKey,
+    pub service_sort_desc: bool,
+
+    // Saved baseline to compare against ('b' saves, 'B' toggles the deltas)
+    pub baseline_name: String,
+    pub baseline: Option<Baseline>,
+    pub show_baseline: bool,
+
+    // Node or service the detail panels stay on while the table selection moves
+    pub pinned_detail: Option<String>,
+
+    // Single list of nodes and services ('u') in place of the split view,
+    // narrowed by a search typed after '/'
+    pub unified_view: bool,
+    pub unified_index: usize,
+    pub unified_query: String,
+    pub unified_typing: bool,
+
+    // Service name filter being typed after '/'; applied to
+    // `filter.selected_service` on Enter
+    pub service_filter_input: Option<String>,
+
+    // Nodes at or above `alerts.temp_critical` as (name, temperature), hottest first
+    pub critical_temps: Vec<(String, f64)>,
+
+    // Per-query success/failure table ('D')
+    pub show_diagnostics: bool,
+    // Full metrics of the selected node in a popup (Enter, Esc closes)
+    pub show_node_detail: bool,
+    // Key binding reference ('?'); takes all keys but quit while open
+    pub show_help: bool,
+
+    // Per-service notes; while one is being typed ('n') this holds the
+    // service and the draft
+    pub notes: Notes,
+    pub note_editing: Option<(String, String)>,
+
+    // PromQL console (':')
+    pub scratchpad: Scratchpad,
 
     // Configuration
     pub config: Config,
 
-    // Prometheus client
-    pub prometheus_client: PrometheusClient,
+    // Power source, re-checked periodically when `ui.battery_saver` is on
+    pub on_battery: bool,
+    // Ticks, polling and health checks are suspended, freezing the display ('p')
+    pub paused: bool,
+    pub battery_checked_at: Option<Instant>,
+
+    // Prometheus client (None if the HTTP client couldn't be built). While an
+    // update runs on a spawned task the client is moved there, and handed
+    // back through `prometheus_update`.
+    pub prometheus_client: Option<PrometheusClient>,
+    prometheus_update: Option<oneshot::Receiver<(PrometheusClient, anyhow::Result<bool>)>>,
+    // The running update was requested with 'R', so the status bar says so
+    pub manual_refresh: bool,
+    // Per-query outcomes as of the last finished update, for the 'D' popup
+    pub query_stats: Vec<(String, QueryStats)>,
+
+    // Health checks running in the background, and when the last round
+    // started (`health_checks.interval_secs`)
+    health_check: Option<oneshot::Receiver<Vec<HealthResult>>>,
+    health_checked_at: Option<Instant>,
+
+    // Node and service CPU history backfilled for a new graph window, swapped
+    // in by `poll_graph_backfill` once it arrives
+    graph_backfill: Option<oneshot::Receiver<anyhow::Result<CpuHistory>>>,
+
+    // Reloads the config file on save (`ui.watch_config`)
+    config_watcher: Option<ConfigWatcher>,
     pub connection_status: ConnectionStatus,
 
+    // Reconnect backoff after a failed poll; reset on the next success
+    pub reconnect_attempt: u32,
+    pub next_retry_at: Option<Instant>,
+
+    // Whether the data on screen ever came from Prometheus, and whether every
+    // query is currently failing (see `prometheus.on_total_failure`)
+    pub has_real_data: bool,
+    pub data_outage: bool,
+
+    // Monitored node this dashboard runs on, if any
+    pub local_node: Option<String>,
+
     // Real-time data
     pub nodes: HashMap<String, NodeMetrics>,
     pub services: HashMap<String, ServiceMetrics>,
-    pub node_history: HashMap<String, Vec<f64>>,
-    pub service_history: HashMap<String, Vec<f64>>,
+    // When each service first appeared, for health check startup grace
+    pub service_first_seen: HashMap<String, Instant>,
+    pub node_history: HashMap<String, VecDeque<f64>>,
+    pub service_history: HashMap<String, VecDeque<f64>>,
+    // Memory usage samples, taken alongside the CPU ones
+    pub memory_history: HashMap<String, VecDeque<f64>>,
+    pub service_memory_history: HashMap<String, VecDeque<f64>>,
+    // Index into GRAPH_WINDOWS the history spans ('+'/'-'), or None for one
+    // sample per second
+    pub graph_window: Option<usize>,
+
+    // Mouse tracking; table areas and scroll offsets are recorded by the renderer each frame
+    pub hovered_row: Option<(ActivePanel, usize)>,
+    pub nodes_table_area: Cell<Rect>,
+    pub services_table_area: Cell<Rect>,
+    pub nodes_table_offset: Cell<usize>,
+    pub services_table_offset: Cell<usize>,
+
+    // Tables whose values changed on the last poll, flashed until the next tick
+    pub flashed_panels: Vec<ActivePanel>,
+    pub flash_tick: u64,
+
+    // Client-side alert rules and their current states
+    pub alert_engine: AlertEngine,
+    // Bell for alerts that just started firing (see `alerts.bell_mode`): the BEL
+    // still to be written, or the tick whose frame is flashed
+    pub bell_pending: bool,
+    pub visual_bell_tick: Option<u64>,
+
+    // Transient message shown in the status bar, and recent errors for bug reports
+    pub status_message: Option<(String, Instant)>,
+    pub recent_errors: Vec<String>,
+}
+
+/// How long a status bar message stays visible
+const STATUS_MESSAGE_TTL: Duration = Duration::from_secs(5);
+
+/// Refresh interval while the battery saver is throttling
+const BATTERY_REFRESH_RATE: Duration = Duration::from_millis(1000);
+
+/// How often the power source is re-checked
+const BATTERY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Upper bound for the delay between reconnection attempts
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Number of errors kept for state dumps
+const MAX_RECENT_ERRORS: usize = 20;
+
+// Health score weight of a `critical` service without its own `weight`, so one
+// critical failure outweighs several minor ones
+const CRITICAL_SERVICE_WEIGHT: f64 = 10.0;
+
+/// Time windows the graphs can be zoomed out to, as (seconds, label)
+pub const GRAPH_WINDOWS: [(u64, &str); 4] = [(300, "5m"), (900, "15m"), (3600, "1h"), (21600, "6h")];
+
+/// Snapshot of the app state written by `App::dump_state`
+#[derive(Serialize)]
+struct StateSnapshot<'a> {
+    created_at: u64,
+    version: &'static str,
+    config: Config,
+    connection_status: &'a ConnectionStatus,
+    theme: &'static str,
+    active_panel: String,
+    selected_node: Option<String>,
+    selected_service: Option<String>,
+    nodes: &'a HashMap<String, NodeMetrics>,
+    services: &'a HashMap<String, ServiceMetrics>,
+    recent_errors: &'a [String],
+}
+
+/// A row of the nodes table: a group heading (see `nodes.group_by`) or a node
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeRow {
+    Group(String),
+    Node(String),
+}
+
+/// An entry of the unified list
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnifiedItem {
+    Node(String),
+    Service(String),
+}
+
+/// Column the nodes table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeSortKey {
+    Name,
+    Cpu,
+    Memory,
+    Gpu,
+    Disk,
+    Temp,
+}
+
+impl NodeSortKey {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Cpu => "cpu",
+            Self::Memory => "memory",
+            Self::Gpu => "gpu",
+            Self::Disk => "disk",
+            Self::Temp => "temp",
+        }
+    }
+
+    /// In the order of the table's columns
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Cpu,
+            Self::Cpu => Self::Memory,
+            Self::Memory => Self::Gpu,
+            Self::Gpu => Self::Disk,
+            Self::Disk => Self::Temp,
+            Self::Temp => Self::Name,
+        }
+    }
+
+    /// The sorted metric, always a percentage or °C so units don't matter
+    fn value(self, node: &NodeMetrics) -> f64 {
+        match self {
+            Self::Name => 0.0,
+            Self::Cpu => node.cpu_usage,
+            Self::Memory => node.memory_usage,
+            Self::Gpu => node.gpu_usage,
+            Self::Disk => node.disk_usage,
+            Self::Temp => node.temperature,
+        }
+    }
+}
+
+/// Column the services table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceSortKey {
+    Name,
+    Status,
+    Cpu,
+    Memory,
+    Rps,
+    Latency,
+    ErrorRate,
+}
+
+impl ServiceSortKey {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Status => "status",
+            Self::Cpu => "cpu",
+            Self::Memory => "memory",
+            Self::Rps => "rps",
+            Self::Latency => "latency",
+            Self::ErrorRate => "errors",
+        }
+    }
+
+    /// In the order of the table's columns
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Status,
+            Self::Status => Self::Cpu,
+            Self::Cpu => Self::Memory,
+            Self::Memory => Self::Rps,
+            Self::Rps => Self::Latency,
+            Self::Latency => Self::ErrorRate,
+            Self::ErrorRate => Self::Name,
+        }
+    }
+
+    fn compare(self, a: &ServiceMetrics, b: &ServiceMetrics) -> Ordering {
+        match self {
+            Self::Name => a.name.cmp(&b.name),
+            Self::Status => a.status.cmp(&b.status),
+            Self::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            Self::Memory => a.memory_usage.total_cmp(&b.memory_usage),
+            Self::Rps => a.requests_per_sec.total_cmp(&b.requests_per_sec),
+            Self::Latency => a.response_time.total_cmp(&b.response_time),
+            Self::ErrorRate => a.error_rate.total_cmp(&b.error_rate),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+/// Name of the configured node matching this machine's hostname, or whose
+/// address is one of our own (the source address used to reach it)
+fn detect_local_node(config: &Config) -> Option<String> {
+    let hostname = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .map(|name| name.trim().to_lowercase())
+        .filter(|name| !name.is_empty());
+
+    config.nodes.nodes
+        .iter()
+        .find(|node| {
+            let by_name = hostname.as_deref().is_some_and(|hostname| {
+                node.name.eq_ignore_ascii_case(hostname) || node.address.eq_ignore_ascii_case(hostname)
+            });
+            by_name || is_local_address(&node.address)
+        })
+        .map(|node| node.name.clone())
+}
+
+/// Whether `address` is an IP of this machine. Connecting a UDP socket sends
+/// nothing; it only picks the local address the OS would route from.
+fn is_local_address(address: &str) -> bool {
+    let Ok(ip) = address.parse::<std::net::IpAddr>() else {
+        return false;
+    };
+    if ip.is_loopback() {
+        return true;
+    }
+    let unspecified = if ip.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    std::net::UdpSocket::bind(unspecified)
+        .and_then(|socket| {
+            socket.connect((ip, 9))?;
+            socket.local_addr()
+        })
+        .is_ok_and(|local| local.ip() == ip)
+}
+
+/// Ticks between history samples: about one a second, or every tick when a
+/// tick is longer than that
+fn history_interval_ticks(refresh_rate_ms: u64) -> u64 {
+    (1000 / refresh_rate_ms.max(1)).max(1)
+}
+
+/// Append a graph sample, dropping the oldest beyond `max_history`
+fn push_sample(history: &mut VecDeque<f64>, value: f64, max_history: usize) {
+    history.push_back(value);
+    while history.len() > max_history {
+        history.pop_front();
+    }
+}
+
+/// CPU samples per node and per service, oldest first
+type CpuHistory = (HashMap<String, Vec<f64>>, HashMap<String, Vec<f64>>);
+
+/// `window_secs` of node and service CPU history in `points` samples
+async fn fetch_cpu_history(client: &PrometheusClient, window_secs: u64, points: usize) -> anyhow::Result<CpuHistory> {
+    let (node_history, service_history) = tokio::join!(
+        client.node_cpu_history(window_secs, points),
+        client.service_cpu_history(window_secs, points),
+    );
+    Ok((node_history?, service_history?))
+}
+
+/// Order two nodes by GPU usage. Nodes without a GPU are not applicable
+/// rather than 0%, so they sort last in either direction.
+pub fn compare_gpu(a: &NodeMetrics, b: &NodeMetrics, descending: bool) -> Ordering {
+    match (a.has_gpu(), b.has_gpu()) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.name.cmp(&b.name),
+        (true, true) => {
+            let order = a.gpu_usage.total_cmp(&b.gpu_usage);
+            if descending { order.reverse() } else { order }
+                .then_with(|| a.name.cmp(&b.name))
+        }
+    }
+}
+
+/// Whether metrics are shown as percentages or absolute values
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayMode {
+    Percent,
+    Absolute,
+}
+
+/// How a node metric is shown: its value, "—" when the node doesn't have it,
+/// or dimmed when it stopped reporting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricAvailability {
+    Available,
+    NotApplicable,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub enum ConnectionStatus {
     Connected,
     Disconnected(String),
@@ -73,120 +443,551 @@ pub enum ConnectionStatus {
 }
 
 impl App {
-    pub async fn new() -> anyhow::Result<Self> {
-        let config = Config::load()?;
-        Self::new_with_config(config).await
-    }
-
-    pub async fn new_with_config(config: Config) -> anyhow::Result<Self> {
-        let theme = match config.general.theme.as_str() {
-            "dark" => Theme::Dracula,
-            "light" => Theme::SolarizedDark,
-            "default" => Theme::Default,
-            "dracula" => Theme::Dracula,
-            "gruvbox" => Theme::GruvboxDark,
-            "nord" => Theme::Nord,
-            "solarized" => Theme::SolarizedDark,
-            "cyberpunk" => Theme::Cyberpunk,
-            "monokai" => Theme::Monokai,
-            "onedark" => Theme::OneDark,
-            "tokyo" => Theme::TokyoNight,
-            _ => Theme::Default,
-        };
-        let theme_colors = ThemeColors::from_theme(theme);
-
-        // Initialize Prometheus client
-        let prometheus_config = PrometheusConfig {
-            url: config.prometheus.url.clone(),
-            timeout_secs: config.prometheus.timeout_secs,
-            query_interval_secs: config.prometheus.query_interval_secs,
-        };
-        let mut prometheus_client = PrometheusClient::new(prometheus_config.clone())?;
-
-        // Test connection and fall back to mock data if needed
-        let (nodes, services, connection_status) = match prometheus_client.test_connection().await {
-            Ok(true) => {
-                // Connection successful, fetch initial data
-                match prometheus_client.update_metrics().await {
-                    Ok(_) => {
-                        let nodes = prometheus_client.get_nodes().clone();
-                        let services = prometheus_client.get_services().clone();
-                        (nodes, services, ConnectionStatus::Connected)
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to fetch initial metrics: {}, falling back to mock data", e);
-                        let (nodes, services) = crate::mock_data::generate_mock_metrics();
-                        (nodes, services, ConnectionStatus::Disconnected(e.to_string()))
-                    }
-                }
-            }
-            Ok(false) => {
-                eprintln!("Prometheus not responding, falling back to mock data");
-                let (nodes, services) = crate::mock_data::generate_mock_metrics();
-                (nodes, services, ConnectionStatus::Disconnected("No response".to_string()))
+    /// App for `config`, read from `config_path` (watched with `ui.watch_config`)
+    pub async fn new_with_config(config: Config, config_path: PathBuf) -> anyhow::Result<Self> {
+        // A client that can't be built (e.g. a broken TLS setup) shouldn't stop
+        // the app from starting, so run on mock data instead
+        let (prometheus_client, (nodes, services, connection_status)) = match Self::prometheus_client(&config) {
+            Ok(mut client) => {
+                let initial = Self::fetch_initial_metrics(&mut client).await;
+                (Some(client), initial)
             }
             Err(e) => {
-                eprintln!("Failed to connect to Prometheus: {}, falling back to mock data", e);
+                tracing::error!("Failed to create Prometheus client: {:#}, falling back to mock data", e);
                 let (nodes, services) = crate::mock_data::generate_mock_metrics();
-                (nodes, services, ConnectionStatus::Disconnected(e.to_string()))
+                (None, (nodes, services, ConnectionStatus::Disconnected(format!("HTTP client unavailable: {:#}", e))))
             }
         };
 
-        Ok(Self {
-            title: "Monitorium - Homelab Monitoring".to_string(),
-            should_quit: false,
-            current_tab: CurrentTab::Overview,
+        let mut app = Self::from_parts(config, prometheus_client, nodes, services, connection_status);
+        app.load_graph_history(app.config.general.history_retention as u64).await;
+        app.local_node = detect_local_node(&app.config);
+        app.apply_auto_theme();
+        match Notes::load() {
+            Ok(notes) => app.notes = notes,
+            Err(e) => app.set_status_message(format!("{:#}", e)),
+        }
+        if app.config.ui.remember_view {
+            match ViewState::load(&app.config.general.cluster_name) {
+                Ok(Some(state)) => app.restore_view_state(state),
+                Ok(None) => {}
+                Err(e) => app.set_status_message(format!("{:#}", e)),
+            }
+        }
+        if app.config.ui.watch_config {
+            match ConfigWatcher::new(config_path) {
+                Ok(watcher) => app.config_watcher = Some(watcher),
+                Err(e) => app.set_status_message(format!("Not watching the config: {:#}", e)),
+            }
+        }
+        Ok(app)
+    }
+
+    /// App on mock data without a Prometheus client, for tests
+    #[cfg(test)]
+    pub fn new_mock(config: Config) -> Self {
+        let (nodes, services) = crate::mock_data::generate_mock_metrics();
+        Self::from_parts(config, None, nodes, services, ConnectionStatus::Disconnected("Mock mode".to_string()))
+    }
+
+    /// Client for `config.prometheus`, querying the configured nodes
+    fn prometheus_client(config: &Config) -> anyhow::Result<PrometheusClient> {
+        let node_targets = config.nodes.nodes.iter().map(|node| (node.name.clone(), node.address.clone())).collect();
+        let mut label_selectors: Vec<_> = config.prometheus.label_selectors.clone().into_iter().collect();
+        label_selectors.sort();
+        PrometheusClient::new(config.prometheus.clone(), node_targets, label_selectors, config.logging.log_prometheus_queries)
+    }
+
+    /// The configured alert rules, plus one for `health_checks.cert_warning_days`
+    fn alert_rules(config: &Config) -> Vec<AlertRule> {
+        // Invalid rules are rejected by `Config::validate` before we get here
+        let mut rules: Vec<AlertRule> = config.alerts.rules.iter().filter_map(|rule| AlertRule::parse(rule).ok()).collect();
+        if let Some(days) = config.health_checks.cert_warning_days {
+            let rule = AlertRuleConfig {
+                name: "Certificate expiring".to_string(),
+                scope: AlertScope::Service,
+                rule: format!("cert_days < {}", days),
+                target: None,
+            };
+            rules.extend(AlertRule::parse(&rule).ok());
+        }
+        rules
+    }
+
+    fn from_parts(
+        config: Config,
+        prometheus_client: Option<PrometheusClient>,
+        nodes: HashMap<String, NodeMetrics>,
+        services: HashMap<String, ServiceMetrics>,
+        connection_status: ConnectionStatus,
+    ) -> Self {
+        let theme = Theme::from_name(&config.general.theme).unwrap_or(Theme::Default);
+        let truecolor = config.ui.color_mode.truecolor();
+        let mut theme_colors = ThemeColors::for_terminal(theme, truecolor);
+        if let Some(colors) = &config.ui.colors {
+            theme_colors.apply_overrides(colors);
+        }
+
+        let recent_errors = match &connection_status {
+            ConnectionStatus::Disconnected(reason) => vec![reason.clone()],
+            _ => Vec::new(),
+        };
+
+        let alert_engine = AlertEngine::new(Self::alert_rules(&config));
+        let has_real_data = matches!(connection_status, ConnectionStatus::Connected);
+
+        let mut app = Self {
+            current_tab: config.ui.tabs.first().copied().unwrap_or(CurrentTab::Overview),
             active_panel: ActivePanel::Nodes,
             selected_node_index: 0,
             selected_service_index: 0,
-            selected_item_index: 0,
             filter: FilterState::new(),
             selected_items: Vec::new(),
             tick_count: 0,
             current_theme: theme,
+            display_mode: DisplayMode::Percent,
+            show_legend: false,
+            show_queries: config.ui.show_queries,
+            node_sort: NodeSortKey::Name,
+            node_sort_desc: false,
+            service_sort: ServiceSortKey::Name,
+            service_sort_desc: false,
+            baseline_name: "default".to_string(),
+            baseline: None,
+            show_baseline: false,
+            pinned_detail: None,
+            unified_view: false,
+            unified_index: 0,
+            unified_query: String::new(),
+            unified_typing: false,
+            service_filter_input: None,
+            critical_temps: Vec::new(),
+            show_diagnostics: false,
+            show_node_detail: false,
+            show_help: false,
+            notes: Notes::default(),
+            note_editing: None,
+            scratchpad: Scratchpad::default(),
             theme_colors,
+            truecolor,
+            auto_theme_light: None,
             config,
+            on_battery: false,
+            paused: false,
+            battery_checked_at: None,
+            query_stats: prometheus_client.as_ref().map(PrometheusClient::query_stats).unwrap_or_default(),
             prometheus_client,
+            prometheus_update: None,
+            manual_refresh: false,
+            config_watcher: None,
+            health_check: None,
+            health_checked_at: None,
+            graph_backfill: None,
             connection_status,
+            reconnect_attempt: 0,
+            next_retry_at: None,
+            has_real_data,
+            data_outage: false,
             node_history: HashMap::new(),
             service_history: HashMap::new(),
+            memory_history: HashMap::new(),
+            service_memory_history: HashMap::new(),
+            graph_window: None,
+            local_node: None,
+            service_first_seen: services.keys().map(|name| (name.clone(), Instant::now())).collect(),
             nodes,
             services,
-        })
+            hovered_row: None,
+            nodes_table_area: Cell::new(Rect::default()),
+            services_table_area: Cell::new(Rect::default()),
+            nodes_table_offset: Cell::new(0),
+            services_table_offset: Cell::new(0),
+            flashed_panels: Vec::new(),
+            flash_tick: 0,
+            alert_engine,
+            bell_pending: false,
+            visual_bell_tick: None,
+            status_message: None,
+            recent_errors,
+        };
+        app.set_theme(theme);
+        app
+    }
+
+    /// Test the connection and fetch the first batch of metrics, falling back to mock data
+    async fn fetch_initial_metrics(client: &mut PrometheusClient) -> (HashMap<String, NodeMetrics>, HashMap<String, ServiceMetrics>, ConnectionStatus) {
+        match client.test_connection().await {
+            Ok(true) => {
+                // Connection successful, fetch initial data
+                match client.update_metrics().await {
+                    Ok(_) => {
+                        let nodes = client.get_nodes().clone();
+                        let services = client.get_services().clone();
+                        (nodes, services, ConnectionStatus::Connected)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch initial metrics: {:#}, falling back to mock data", e);
+                        let (nodes, services) = crate::mock_data::generate_mock_metrics();
+                        (nodes, services, ConnectionStatus::Disconnected(e.to_string()))
+                    }
+                }
+            }
+            Ok(false) => {
+                tracing::warn!("Prometheus not responding, falling back to mock data");
+                let (nodes, services) = crate::mock_data::generate_mock_metrics();
+                (nodes, services, ConnectionStatus::Disconnected("No response".to_string()))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to connect to Prometheus: {:#}, falling back to mock data", e);
+                let (nodes, services) = crate::mock_data::generate_mock_metrics();
+                (nodes, services, ConnectionStatus::Disconnected(e.to_string()))
+            }
+        }
     }
 
     pub fn on_tick(&mut self) {
         self.tick_count += 1;
+        if self.config_watcher.as_mut().is_some_and(ConfigWatcher::poll) {
+            self.reload_config();
+        }
+        self.apply_auto_theme();
+        if self.config.ui.battery_saver && self.battery_checked_at.is_none_or(|at| at.elapsed() >= BATTERY_CHECK_INTERVAL) {
+            self.on_battery = crate::power::on_battery();
+            self.battery_checked_at = Some(Instant::now());
+        }
+        for name in self.services.keys() {
+            self.service_first_seen.entry(name.clone()).or_insert_with(Instant::now);
+        }
         self.update_history();
+        let mut newly_firing = self.alert_engine.evaluate(&self.nodes, &self.services);
+        newly_firing += self.update_critical_temps();
+        if newly_firing > 0 {
+            match self.config.alerts.bell_mode {
+                BellMode::Audible => self.bell_pending = true,
+                BellMode::Visual => self.visual_bell_tick = Some(self.tick_count),
+                BellMode::None => {}
+            }
+        }
+    }
+
+    /// Re-read the watched config file and apply it. An invalid file keeps
+    /// the running config and shows why in the status bar.
+    fn reload_config(&mut self) {
+        let Some(path) = self.config_watcher.as_ref().map(|watcher| watcher.path().clone()) else {
+            return;
+        };
+        let loaded = Config::load_from(&path).and_then(|(config, mut warnings)| {
+            warnings.extend(config.validate()?);
+            Ok((config, warnings))
+        });
+        let (config, warnings) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.set_status_message(format!("Config reload failed, keeping the old one: {:#}", e));
+                return;
+            }
+        };
+
+        // A new client starts polling right away; drop any update still in flight
+        match Self::prometheus_client(&config) {
+            Ok(client) => {
+                self.prometheus_client = Some(client);
+                self.prometheus_update = None;
+                self.manual_refresh = false;
+                self.next_retry_at = None;
+                self.reconnect_attempt = 0;
+            }
+            Err(e) => {
+                self.set_status_message(format!("Config reload failed, keeping the old one: {:#}", e));
+                return;
+            }
+        }
+
+        self.alert_engine = AlertEngine::new(Self::alert_rules(&config));
+        self.health_checked_at = None;
+        let theme_changed = config.general.theme != self.config.general.theme;
+        self.truecolor = config.ui.color_mode.truecolor();
+        // The namespace may have changed, and with it the services table
+        let selected_service = self.selected_service_name();
+        self.config = config;
+        self.reselect_service(selected_service);
+        self.auto_theme_light = None;
+        let theme = if theme_changed {
+            Theme::from_name(&self.config.general.theme).unwrap_or(Theme::Default)
+        } else {
+            self.current_theme
+        };
+        // The TUI owns the terminal, so warnings go to the log and the status bar
+        for warning in &warnings {
+            tracing::warn!("{}", warning);
+        }
+        match warnings.first() {
+            Some(warning) => self.set_status_message(format!("Config reloaded with warnings: {}", warning)),
+            None => self.set_status_message(format!("Config reloaded from {}", path.display())),
+        }
+        // After the toast, so a contrast warning replaces it
+        self.set_theme(theme);
+        self.apply_auto_theme();
+    }
+
+    /// Recheck nodes against `alerts.temp_critical`, returning how many just crossed it
+    fn update_critical_temps(&mut self) -> usize {
+        let Some(limit) = self.config.alerts.temp_critical else {
+            return 0;
+        };
+        let mut critical: Vec<(String, f64)> = self.nodes
+            .values()
+            .filter(|node| node.temperature >= limit)
+            .map(|node| (node.name.clone(), node.temperature))
+            .collect();
+        critical.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let newly_critical = critical.iter().filter(|(name, _)| !self.critical_temps.iter().any(|(old, _)| old == name)).count();
+        self.critical_temps = critical;
+        newly_critical
+    }
+
+    /// Whether the BEL byte should be written now; clears the request
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell_pending)
+    }
+
+    /// Whether this frame is the visual bell's flash
+    pub fn visual_bell_active(&self) -> bool {
+        self.visual_bell_tick == Some(self.tick_count)
+    }
+
+    /// Whether the battery saver is currently slowing down refreshes
+    pub fn battery_throttled(&self) -> bool {
+        self.config.ui.battery_saver && self.on_battery
+    }
+
+    /// UI refresh interval, throttled while on battery
+    pub fn tick_rate(&self) -> Duration {
+        let configured = Duration::from_millis(self.config.ui.refresh_rate_ms);
+        if self.battery_throttled() {
+            configured.max(BATTERY_REFRESH_RATE)
+        } else {
+            configured
+        }
+    }
+
+    /// Start a Prometheus update on a spawned task, so a slow server doesn't
+    /// freeze keys and redraws; `poll_prometheus_update` applies the result.
+    /// Does nothing while the previous update is still in flight.
+    pub fn start_prometheus_update(&mut self) {
+        if self.prometheus_update.is_some() {
+            return;
+        }
+        let Some(mut prometheus_client) = self.prometheus_client.take() else {
+            // No HTTP client, keep animating mock data
+            self.keeping_selection(Self::update_mock_metrics);
+            return;
+        };
+
+        // Hold off until the backoff delay has passed
+        if self.next_retry_at.is_some_and(|at| Instant::now() < at) {
+            self.prometheus_client = Some(prometheus_client);
+            self.keeping_selection(Self::on_data_unavailable);
+            return;
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let result = prometheus_client.update_metrics().await;
+            // The receiver is only gone if the app is quitting
+            let _ = sender.send((prometheus_client, result));
+        });
+        self.prometheus_update = Some(receiver);
+    }
+
+    /// Poll Prometheus now instead of waiting for `query_interval_secs` or a
+    /// reconnect backoff to pass ('R')
+    pub fn force_refresh(&mut self) {
+        if self.paused {
+            self.set_status_message("Paused (p to resume)");
+            return;
+        }
+        if self.prometheus_update.is_some() {
+            return;
+        }
+        let Some(prometheus_client) = self.prometheus_client.as_mut() else {
+            self.set_status_message("No Prometheus client, showing mock data");
+            return;
+        };
+        prometheus_client.force_next_update();
+        self.next_retry_at = None;
+        self.manual_refresh = true;
+        self.start_prometheus_update();
+    }
+
+    /// Run every enabled http(s) health check on a spawned task, once per
+    /// `health_checks.interval_secs`
+    pub fn start_health_check(&mut self) {
+        let health_checks = &self.config.health_checks;
+        if !health_checks.enabled
+            || self.health_check.is_some()
+            || self.health_checked_at.is_some_and(|at| at.elapsed() < Duration::from_secs(health_checks.interval_secs))
+        {
+            return;
+        }
+        self.health_checked_at = Some(Instant::now());
+
+        // Other schemes (e.g. postgres://) are rejected with a warning at startup
+        let checks: Vec<ServiceHealthCheck> = health_checks.services
+            .iter()
+            .filter(|check| check.enabled && (check.endpoint.starts_with("http://") || check.endpoint.starts_with("https://")))
+            .cloned()
+            .collect();
+        let client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(health_checks.timeout_secs))
+            .tls_info(health_checks.cert_warning_days.is_some())
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                self.record_error(format!("Failed to build the health check client: {}", e));
+                return;
+            }
+        };
+
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let mut tasks = tokio::task::JoinSet::new();
+            for check in checks {
+                let client = client.clone();
+                tasks.spawn(async move { crate::health_checker::run_health_check(&client, &check).await });
+            }
+            let mut results = Vec::new();
+            while let Some(result) = tasks.join_next().await {
+                results.extend(result.ok());
+            }
+            let _ = sender.send(results);
+        });
+        self.health_check = Some(receiver);
+    }
+
+    /// Apply the health check results once the round has finished
+    pub fn poll_health_check(&mut self) {
+        let Some(receiver) = self.health_check.as_mut() else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(results) => {
+                self.health_check = None;
+                for result in results {
+                    self.apply_health_result(result);
+                }
+            }
+            Err(oneshot::error::TryRecvError::Empty) => {}
+            Err(oneshot::error::TryRecvError::Closed) => self.health_check = None,
+        }
+    }
+
+    /// A passing check makes the service healthy, or degraded when slow. It
+    /// only turns unhealthy after `failure_threshold` failures in a row.
+    fn apply_health_result(&mut self, result: HealthResult) {
+        let failure_threshold = self.config.health_checks.failure_threshold;
+        let Some(service) = self.services.get_mut(&result.name) else {
+            return;
+        };
+        service.last_health_check = result.checked_at;
+        service.health_response_time = result.response_time_ms;
+        // A failed handshake has no certificate; keep the last good date
+        if result.cert_expires_at.is_some() {
+            service.cert_expires_at = result.cert_expires_at;
+        }
+        if self.config.logging.log_health_checks {
+            match &result.error {
+                None => tracing::info!("Health check {} passed in {:.0}ms", result.name, result.response_time_ms),
+                Some(error) => tracing::warn!("Health check {} failed: {}", result.name, error),
+            }
+        }
+        if result.passed {
+            service.consecutive_failures = 0;
+            service.health_status = if result.slow { "Degraded" } else { "Healthy" }.to_string();
+            return;
+        }
+        service.consecutive_failures += 1;
+        if service.consecutive_failures >= failure_threshold {
+            service.health_status = "Unhealthy".to_string();
+        }
+        // Record the error once, when the service turns unhealthy
+        if service.consecutive_failures == failure_threshold.max(1) {
+            let error = result.error.unwrap_or_default();
+            self.record_error(format!("Health check {}: {}", result.name, error));
+        }
+    }
+
+    /// Whether a Prometheus update is running in the background
+    pub fn prometheus_update_in_flight(&self) -> bool {
+        self.prometheus_update.is_some()
+    }
+
+    /// Apply the background Prometheus update if it has finished
+    pub fn poll_prometheus_update(&mut self) {
+        let Some(receiver) = self.prometheus_update.as_mut() else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok((prometheus_client, result)) => {
+                self.prometheus_update = None;
+                self.manual_refresh = false;
+                self.query_stats = prometheus_client.query_stats();
+                self.keeping_selection(|app| app.apply_prometheus_update(&prometheus_client, result));
+                self.prometheus_client = Some(prometheus_client);
+            }
+            Err(oneshot::error::TryRecvError::Empty) => {}
+            Err(oneshot::error::TryRecvError::Closed) => {
+                // The task panicked and took the client with it
+                self.prometheus_update = None;
+                self.manual_refresh = false;
+                self.connection_status = ConnectionStatus::Disconnected("Prometheus update task failed".to_string());
+                self.record_error("Prometheus update task failed".to_string());
+            }
+        }
     }
 
-    pub async fn update_prometheus_metrics(&mut self) {
-        // Try to update metrics from Prometheus
-        match self.prometheus_client.update_metrics().await {
+    fn apply_prometheus_update(&mut self, prometheus_client: &PrometheusClient, result: anyhow::Result<bool>) {
+        match result {
             Ok(updated) => {
                 if updated {
                     // Successfully updated, update existing data while preserving structure
-                    let new_nodes = self.prometheus_client.get_nodes();
-                    let new_services = self.prometheus_client.get_services();
+                    let new_nodes = prometheus_client.get_nodes();
+                    let new_services = prometheus_client.get_services();
+
+                    let mut changed = Vec::new();
 
                     // Update values for existing nodes without changing order
                     for (name, new_node) in new_nodes.iter() {
                         if let Some(existing_node) = self.nodes.get_mut(name) {
+                            let before = existing_node.clone();
                             // Update only the metrics, preserve hardware specs
                             existing_node.cpu_usage = new_node.cpu_usage;
                             existing_node.memory_usage = new_node.memory_usage;
+                            existing_node.swap_usage = new_node.swap_usage;
                             existing_node.gpu_usage = new_node.gpu_usage;
                             existing_node.gpu_memory = new_node.gpu_memory;
                             existing_node.network_rx = new_node.network_rx;
                             existing_node.network_tx = new_node.network_tx;
+                            existing_node.network_errors = new_node.network_errors;
+                            existing_node.network_drops = new_node.network_drops;
                             existing_node.disk_usage = new_node.disk_usage;
                             existing_node.temperature = new_node.temperature;
+                            existing_node.load_avg = new_node.load_avg;
+                            existing_node.uptime = new_node.uptime;
+                            existing_node.top_processes = new_node.top_processes.clone();
+                            existing_node.stale_metrics = new_node.stale_metrics.clone();
+                            existing_node.unavailable_metrics = new_node.unavailable_metrics.clone();
+                            if *existing_node != before && !changed.contains(&ActivePanel::Nodes) {
+                                changed.push(ActivePanel::Nodes);
+                            }
                         }
                     }
 
                     // Update values for existing services without changing order
                     for (name, new_service) in new_services.iter() {
                         if let Some(existing_service) = self.services.get_mut(name) {
+                            let before = existing_service.clone();
                             // Update only the metrics, preserve basic info
                             existing_service.cpu_usage = new_service.cpu_usage;
                             existing_service.memory_usage = new_service.memory_usage;
@@ -195,37 +996,170 @@ impl App {
                             existing_service.error_rate = new_service.error_rate;
                             existing_service.status = new_service.status.clone();
                             existing_service.ready_replicas = new_service.ready_replicas;
+                            existing_service.age = new_service.age;
+                            existing_service.image = new_service.image.clone();
+                            existing_service.events = new_service.events.clone();
+                            existing_service.stale_metrics = new_service.stale_metrics.clone();
+                            if *existing_service != before && !changed.contains(&ActivePanel::Services) {
+                                changed.push(ActivePanel::Services);
+                            }
                         }
                     }
 
+                    if self.config.ui.flash_updates {
+                        self.flashed_panels = changed;
+                        self.flash_tick = self.tick_count;
+                    }
+
                     self.connection_status = ConnectionStatus::Connected;
+                    self.reconnect_attempt = 0;
+                    self.next_retry_at = None;
+                    self.has_real_data = true;
+                    self.data_outage = false;
                 }
             }
             Err(e) => {
-                eprintln!("Failed to update Prometheus metrics: {}", e);
-                self.connection_status = ConnectionStatus::Disconnected(e.to_string());
-
-                // Fall back to mock data updates if Prometheus is disconnected
-                self.update_mock_metrics();
+                tracing::error!("Failed to update Prometheus metrics: {:#}", e);
+                self.connection_status = ConnectionStatus::Connecting;
+                self.record_error(e.to_string());
+                self.reconnect_attempt += 1;
+                self.next_retry_at = Some(Instant::now() + self.reconnect_backoff());
+                self.on_data_unavailable();
             }
         }
     }
 
-    fn update_mock_metrics(&mut self) {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
+    /// Run a metrics update, keeping the selected node and service selected
+    /// when a sort by a metric reorders their tables
+    fn keeping_selection(&mut self, update: impl FnOnce(&mut Self)) {
+        let node = self.selected_node_name();
+        let service = self.selected_service_name();
+        update(self);
+        self.reselect_node(node);
+        self.reselect_service(service);
+    }
 
-        // Only update mock metrics minimally to avoid graph jumping
-        // Small changes for realistic variation but keep graphs mostly stable
-        for node in self.nodes.values_mut() {
-            // Much smaller ranges to keep graphs stable
-            node.cpu_usage = (node.cpu_usage + rng.gen_range(-0.5..0.5)).clamp(10.0, 95.0);
-            node.memory_usage = (node.memory_usage + rng.gen_range(-0.3..0.3)).clamp(20.0, 85.0);
-            node.gpu_usage = (node.gpu_usage + rng.gen_range(-1.0..1.0)).clamp(0.0, 100.0);
-            node.gpu_memory = (node.gpu_memory + rng.gen_range(-0.2..0.2)).clamp(30.0, 70.0);
-            node.network_rx = (node.network_rx + rng.gen_range(-10.0..20.0)).clamp(0.0, 1000.0);
-            node.network_tx = (node.network_tx + rng.gen_range(-8.0..15.0)).clamp(0.0, 800.0);
-            node.disk_usage = (node.disk_usage + rng.gen_range(-0.1..0.1)).clamp(40.0, 90.0);
+    /// Apply `on_total_failure` while no query succeeds. Until real data has
+    /// been seen we're in mock mode anyway, so keep animating it.
+    fn on_data_unavailable(&mut self) {
+        let policy = self.config.prometheus.on_total_failure;
+        if !self.has_real_data || policy == TotalFailurePolicy::Mock {
+            self.update_mock_metrics();
+            return;
+        }
+
+        // Everything `apply_prometheus_update` copies from a poll
+        if policy == TotalFailurePolicy::Blank && !self.data_outage {
+            for node in self.nodes.values_mut() {
+                node.cpu_usage = 0.0;
+                node.memory_usage = 0.0;
+                node.swap_usage = 0.0;
+                node.gpu_usage = 0.0;
+                node.gpu_memory = 0.0;
+                node.network_rx = 0.0;
+                node.network_tx = 0.0;
+                node.network_errors = 0.0;
+                node.network_drops = 0.0;
+                node.disk_usage = 0.0;
+                node.temperature = 0.0;
+                node.load_avg = [0.0; 3];
+                node.uptime = 0;
+                node.top_processes.clear();
+                node.stale_metrics.clear();
+                node.unavailable_metrics.clear();
+            }
+            for service in self.services.values_mut() {
+                service.cpu_usage = 0.0;
+                service.memory_usage = 0.0;
+                service.requests_per_sec = 0.0;
+                service.response_time = 0.0;
+                service.error_rate = 0.0;
+                service.status = "Unknown".to_string();
+                service.ready_replicas = 0;
+                service.age = 0;
+                service.image = "unknown".to_string();
+                service.events.clear();
+                service.stale_metrics.clear();
+            }
+        }
+        self.data_outage = true;
+    }
+
+    /// Active view modifiers for the status bar, e.g. "ns:homelab · sort:gpu▼"
+    pub fn view_breadcrumb(&self) -> String {
+        let mut parts = vec![format!("ns:{}", self.config.general.default_namespace)];
+        if self.node_sort != NodeSortKey::Name || self.node_sort_desc {
+            parts.push(format!("sort:{}{}", self.node_sort.label(), if self.node_sort_desc { "▼" } else { "▲" }));
+        }
+        if self.service_sort != ServiceSortKey::Name || self.service_sort_desc {
+            parts.push(format!("svc-sort:{}{}", self.service_sort.label(), if self.service_sort_desc { "▼" } else { "▲" }));
+        }
+        if self.filter.enabled {
+            parts.push("filter:on".to_string());
+        }
+        if let Some(text) = &self.filter.selected_service {
+            parts.push(format!("svc:/{}", text));
+        }
+        if self.display_mode == DisplayMode::Absolute {
+            parts.push("units:abs".to_string());
+        }
+        if !self.selected_items.is_empty() {
+            parts.push(format!("selected:{}", self.selected_items.len()));
+        }
+        if self.unified_view {
+            parts.push("view:unified".to_string());
+        }
+        if !self.unified_query.is_empty() {
+            parts.push(format!("search:{}", self.unified_query));
+        }
+        if let Some(pinned) = &self.pinned_detail {
+            parts.push(format!("pin:{}", pinned));
+        }
+        if let Some(baseline) = self.active_baseline() {
+            parts.push(format!("vs:{}", baseline.name));
+        }
+        if let Some(window) = self.graph_window_label() {
+            parts.push(format!("window:{}", window));
+        }
+        if self.data_frozen() {
+            parts.push("FROZEN".to_string());
+        } else if self.no_data() {
+            parts.push("NO DATA".to_string());
+        }
+        parts.join(" · ")
+    }
+
+    /// Whether a table's values changed on the poll since the last tick
+    pub fn is_flashing(&self, panel: ActivePanel) -> bool {
+        self.flash_tick == self.tick_count && self.flashed_panels.contains(&panel)
+    }
+
+    /// Last real values are shown greyed out because every query is failing
+    pub fn data_frozen(&self) -> bool {
+        self.data_outage && self.config.prometheus.on_total_failure == TotalFailurePolicy::Freeze
+    }
+
+    /// Values were cleared because every query is failing
+    pub fn no_data(&self) -> bool {
+        self.data_outage && self.config.prometheus.on_total_failure == TotalFailurePolicy::Blank
+    }
+
+    fn update_mock_metrics(&mut self) {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+
+        // Only update mock metrics minimally to avoid graph jumping
+        // Small changes for realistic variation but keep graphs mostly stable
+        for node in self.nodes.values_mut() {
+            // Much smaller ranges to keep graphs stable
+            node.cpu_usage = (node.cpu_usage + rng.gen_range(-0.5..0.5)).clamp(10.0, 95.0);
+            node.memory_usage = (node.memory_usage + rng.gen_range(-0.3..0.3)).clamp(20.0, 85.0);
+            node.gpu_usage = (node.gpu_usage + rng.gen_range(-1.0..1.0)).clamp(0.0, 100.0);
+            node.gpu_memory = (node.gpu_memory + rng.gen_range(-0.2..0.2)).clamp(30.0, 70.0);
+            node.network_rx = (node.network_rx + rng.gen_range(-10.0..20.0)).clamp(0.0, 1000.0);
+            node.network_tx = (node.network_tx + rng.gen_range(-8.0..15.0)).clamp(0.0, 800.0);
+            node.disk_usage = (node.disk_usage + rng.gen_range(-0.1..0.1)).clamp(40.0, 90.0);
+            node.load_avg[0] = (node.load_avg[0] + rng.gen_range(-0.1..0.1)).max(0.0);
         }
 
         // Update service metrics with minimal changes
@@ -240,66 +1174,685 @@ impl App {
 
     fn update_history(&mut self) {
         let max_history = self.config.general.history_retention;
-        let update_interval = (1000 / self.config.ui.refresh_rate_ms) as u64; // Convert to ticks
+        let update_interval = history_interval_ticks(self.config.ui.refresh_rate_ms) * self.history_step_secs();
 
         // Only update history at configured intervals
-        if self.tick_count % update_interval != 0 {
+        if !self.tick_count.is_multiple_of(update_interval) {
             return;
         }
 
         for (node_name, node) in &self.nodes {
-            let history = self.node_history.entry(node_name.clone()).or_insert_with(Vec::new);
-            history.push(node.cpu_usage);
-            if history.len() > max_history {
-                history.remove(0);
-            }
+            push_sample(self.node_history.entry(node_name.clone()).or_default(), node.cpu_usage, max_history);
+            push_sample(self.memory_history.entry(node_name.clone()).or_default(), node.memory_usage, max_history);
         }
 
         // For services, use CPU usage instead of fake RPS since we don't have real RPS data
         for (service_name, service) in &self.services {
-            let history = self.service_history.entry(service_name.clone()).or_insert_with(Vec::new);
-            history.push(service.cpu_usage);
-            if history.len() > max_history {
-                history.remove(0);
+            push_sample(self.service_history.entry(service_name.clone()).or_default(), service.cpu_usage, max_history);
+            push_sample(self.service_memory_history.entry(service_name.clone()).or_default(), service.memory_usage, max_history);
+        }
+    }
+
+    /// Seconds between history samples, so that `history_retention` samples
+    /// span the graph window
+    fn history_step_secs(&self) -> u64 {
+        match self.graph_window {
+            Some(index) => (GRAPH_WINDOWS[index].0 / self.config.general.history_retention as u64).max(1),
+            None => 1,
+        }
+    }
+
+    /// Label of the graph window, unless the graphs are live
+    pub fn graph_window_label(&self) -> Option<&'static str> {
+        self.graph_window.map(|index| GRAPH_WINDOWS[index].1)
+    }
+
+    /// Step to the next longer (`wider`) or shorter graph window, skipping
+    /// windows shorter than the retained samples can cover at one per second.
+    /// Returns whether the window changed; the history then needs refilling.
+    pub fn zoom_graphs(&mut self, wider: bool) -> bool {
+        let retention = self.config.general.history_retention as u64;
+        let available: Vec<usize> = (0..GRAPH_WINDOWS.len()).filter(|&index| GRAPH_WINDOWS[index].0 >= retention).collect();
+        let position = self.graph_window.and_then(|window| available.iter().position(|&index| index == window));
+        let window = match (wider, position) {
+            (true, None) => available.first().copied(),
+            (true, Some(position)) => Some(available.get(position + 1).copied().unwrap_or(available[position])),
+            (false, None) => None,
+            (false, Some(0)) => None,
+            (false, Some(position)) => Some(available[position - 1]),
+        };
+        if window == self.graph_window {
+            return false;
+        }
+        self.graph_window = window;
+        self.set_status_message(format!("Graph window: {}", self.graph_window_label().unwrap_or("live")));
+        true
+    }
+
+    /// Restart the graph history at the current window's step. When
+    /// Prometheus is reachable, CPU is backfilled on a spawned task with a
+    /// client of its own, and the old history stays up until it arrives.
+    pub fn refill_graph_history(&mut self) {
+        // A backfill for the previous window is stale now
+        self.graph_backfill = None;
+        let Some(index) = self.graph_window.filter(|_| matches!(self.connection_status, ConnectionStatus::Connected)) else {
+            self.set_graph_history(Ok((HashMap::new(), HashMap::new())));
+            return;
+        };
+        let client = match Self::prometheus_client(&self.config) {
+            Ok(client) => client,
+            Err(e) => {
+                self.set_graph_history(Err(e));
+                return;
+            }
+        };
+        let points = self.config.general.history_retention;
+        let (sender, receiver) = oneshot::channel();
+        tokio::spawn(async move {
+            let _ = sender.send(fetch_cpu_history(&client, GRAPH_WINDOWS[index].0, points).await);
+        });
+        self.graph_backfill = Some(receiver);
+    }
+
+    /// Swap in the backfilled graph history if it has arrived
+    pub fn poll_graph_backfill(&mut self) {
+        let Some(receiver) = self.graph_backfill.as_mut() else {
+            return;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => Err(anyhow::anyhow!("backfill task stopped")),
+        };
+        self.graph_backfill = None;
+        self.set_graph_history(result);
+    }
+
+    /// Fill the CPU graphs with `window_secs` of range-query history, so they
+    /// show a trend straight away instead of building up one tick at a time
+    async fn load_graph_history(&mut self, window_secs: u64) {
+        let Some(client) = &self.prometheus_client else {
+            return;
+        };
+        if !matches!(self.connection_status, ConnectionStatus::Connected) {
+            return;
+        }
+        let history = fetch_cpu_history(client, window_secs, self.config.general.history_retention).await;
+        self.set_graph_history(history);
+    }
+
+    /// Replace the graph history with backfilled CPU history; memory restarts
+    /// empty, as does everything when the backfill failed
+    fn set_graph_history(&mut self, history: anyhow::Result<CpuHistory>) {
+        self.node_history.clear();
+        self.service_history.clear();
+        self.memory_history.clear();
+        self.service_memory_history.clear();
+        match history {
+            Ok((nodes, services)) => {
+                self.node_history = nodes.into_iter().map(|(name, history)| (name, history.into())).collect();
+                self.service_history = services.into_iter().map(|(name, history)| (name, history.into())).collect();
+            }
+            Err(e) => self.set_status_message(format!("Graph backfill failed: {:#}", e)),
+        }
+    }
+
+    /// Node names in the order the nodes table displays them
+    pub fn sorted_node_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.nodes.keys().cloned().collect();
+        match self.node_sort {
+            NodeSortKey::Name => names.sort(),
+            NodeSortKey::Gpu => names.sort_by(|a, b| compare_gpu(&self.nodes[a], &self.nodes[b], self.node_sort_desc)),
+            key => names.sort_by(|a, b| {
+                let order = key.value(&self.nodes[a]).total_cmp(&key.value(&self.nodes[b]));
+                if self.node_sort_desc { order.reverse() } else { order }.then_with(|| a.cmp(b))
+            }),
+        }
+        if self.node_sort == NodeSortKey::Name && self.node_sort_desc {
+            names.reverse();
+        }
+        if self.config.nodes.group_by.is_some() {
+            // Stable, so the chosen sort still applies within each group
+            names.sort_by_cached_key(|name| self.node_group(name));
+        }
+        names
+    }
+
+    /// Value of the `nodes.group_by` label for a node; nodes without it are
+    /// grouped last
+    fn node_group(&self, node_name: &str) -> (bool, String) {
+        let value = self.config.nodes.group_by.as_ref().and_then(|label| {
+            self.config.nodes.nodes
+                .iter()
+                .find(|entry| entry.name == node_name)
+                .and_then(|entry| entry.labels.as_ref()?.get(label).cloned())
+        });
+        (value.is_none(), value.unwrap_or_else(|| "(unlabeled)".to_string()))
+    }
+
+    /// Rows of the nodes table in display order, with a heading before each
+    /// group when `nodes.group_by` is set
+    pub fn node_rows(&self) -> Vec<NodeRow> {
+        let mut rows = Vec::new();
+        let mut current_group = None;
+        for name in self.sorted_node_names() {
+            if self.config.nodes.group_by.is_some() {
+                let (_, group) = self.node_group(&name);
+                if current_group.as_ref() != Some(&group) {
+                    rows.push(NodeRow::Group(group.clone()));
+                    current_group = Some(group);
+                }
+            }
+            rows.push(NodeRow::Node(name));
+        }
+        rows
+    }
+
+    /// Health status to display: an unhealthy service is "Starting" while it
+    /// is within its configured `startup_grace_secs`
+    pub fn health_status<'a>(&self, service: &'a ServiceMetrics) -> &'a str {
+        if service.health_status == "Unhealthy" && self.in_startup_grace(&service.name) {
+            "Starting"
+        } else {
+            &service.health_status
+        }
+    }
+
+    /// Cluster health from 0 (everything down) to 1 (all healthy), each
+    /// service counted by its weight; `None` while no service has a known health
+    pub fn weighted_health_score(&self) -> Option<f64> {
+        let mut total = 0.0;
+        let mut healthy = 0.0;
+        for service in self.services.values() {
+            let value = match self.health_status(service) {
+                "Healthy" => 1.0,
+                "Starting" | "Degraded" => 0.5,
+                "Unhealthy" => 0.0,
+                _ => continue,
+            };
+            let weight = self.service_weight(&service.name);
+            total += weight;
+            healthy += weight * value;
+        }
+        (total > 0.0).then(|| healthy / total)
+    }
+
+    /// `weight` of the service's health check, defaulting to
+    /// `CRITICAL_SERVICE_WEIGHT` for critical services and 1 otherwise
+    fn service_weight(&self, service_name: &str) -> f64 {
+        let Some(check) = self.config.health_checks.services.iter().find(|check| check.name == service_name) else {
+            return 1.0;
+        };
+        check.weight.unwrap_or(if check.critical { CRITICAL_SERVICE_WEIGHT } else { 1.0 })
+    }
+
+    fn in_startup_grace(&self, service_name: &str) -> bool {
+        let grace = self.config.health_checks.services
+            .iter()
+            .find(|check| check.name == service_name)
+            .map_or(0, |check| check.startup_grace_secs);
+        self.service_first_seen
+            .get(service_name)
+            .is_some_and(|first_seen| first_seen.elapsed() < Duration::from_secs(grace))
+    }
+
+    /// The configured image when the service is known to run a different one.
+    /// An expectation without ':' is compared against the image tag only.
+    pub fn unexpected_image(&self, service: &ServiceMetrics) -> Option<&str> {
+        let expected = self.config.expected_images.get(&service.name)?;
+        if service.image == "unknown" {
+            return None;
+        }
+        let matches = if expected.contains(':') {
+            service.image == *expected
+        } else {
+            service.image.rsplit_once(':').is_some_and(|(_, tag)| tag == expected)
+        };
+        (!matches).then_some(expected.as_str())
+    }
+
+    /// Service names in the order the services table displays them
+    pub fn filtered_service_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.services
+            .iter()
+            .filter(|(_name, service)| service.namespace == self.config.general.default_namespace)
+            .filter(|(name, _)| self.filter.selected_service.as_ref().is_none_or(|text| name.contains(text.as_str())))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort_by(|a, b| {
+            let order = self.service_sort.compare(&self.services[a], &self.services[b]);
+            if self.service_sort_desc { order.reverse() } else { order }.then_with(|| a.cmp(b))
+        });
+        names
+    }
+
+    /// Whether `metric` ("cpu", "gpu", "temperature", ...) applies to the
+    /// node and reported on the last poll. GPU also follows `show_gpu`.
+    pub fn node_metric_availability(&self, node: &NodeMetrics, metric: &str) -> MetricAvailability {
+        let gpu_missing = metric == "gpu" && !(self.node_shows_gpu(&node.name) && node.has_gpu());
+        if gpu_missing || node.unavailable_metrics.contains(metric) {
+            MetricAvailability::NotApplicable
+        } else if node.stale_metrics.contains(metric) {
+            MetricAvailability::Failed
+        } else {
+            MetricAvailability::Available
+        }
+    }
+
+    /// `show_gpu` from the node's overrides, or from the node defaults
+    fn node_shows_gpu(&self, node_name: &str) -> bool {
+        self.node_settings(node_name).show_gpu
+    }
+
+    /// "C" or "F", from the node's overrides or the defaults
+    pub fn node_temperature_unit(&self, node_name: &str) -> &str {
+        &self.node_settings(node_name).temperature_unit
+    }
+
+    /// "KB/s", "MB/s" or "GB/s", from the node's overrides or the defaults
+    pub fn node_network_unit(&self, node_name: &str) -> &str {
+        &self.node_settings(node_name).network_unit
+    }
+
+    /// The node's `overrides`, or `nodes.defaults` without any
+    fn node_settings(&self, node_name: &str) -> &NodeDefaults {
+        let nodes = &self.config.nodes;
+        nodes.nodes
+            .iter()
+            .find(|entry| entry.name == node_name)
+            .and_then(|entry| entry.overrides.as_ref())
+            .unwrap_or(&nodes.defaults)
+    }
+
+    /// Detail layout for a node, picked by its configured layout label
+    pub fn node_layout(&self, node_name: &str) -> NodeLayout {
+        let nodes = &self.config.nodes;
+        nodes.nodes
+            .iter()
+            .find(|entry| entry.name == node_name)
+            .and_then(|entry| entry.labels.as_ref()?.get(&nodes.layout_label))
+            .and_then(|value| nodes.layouts.get(value).copied())
+            .unwrap_or_default()
+    }
+
+    /// Services reachable from `service_name` through the dependency config,
+    /// as (depth, name) in tree order. Upstream follows what the service
+    /// depends on, downstream follows what depends on it.
+    pub fn dependency_tree(&self, service_name: &str, upstream: bool) -> Vec<(usize, String)> {
+        let mut tree = Vec::new();
+        let mut visited = HashSet::from([service_name.to_string()]);
+        self.walk_dependencies(service_name, upstream, 1, &mut visited, &mut tree);
+        tree
+    }
+
+    fn walk_dependencies(&self, service_name: &str, upstream: bool, depth: usize, visited: &mut HashSet<String>, tree: &mut Vec<(usize, String)>) {
+        let dependencies = &self.config.dependencies;
+        let mut next: Vec<&String> = if upstream {
+            dependencies.get(service_name).map(|deps| deps.iter().collect()).unwrap_or_default()
+        } else {
+            dependencies
+                .iter()
+                .filter(|(_, deps)| deps.iter().any(|dep| dep == service_name))
+                .map(|(name, _)| name)
+                .collect()
+        };
+        next.sort();
+
+        for name in next {
+            // Guard against cycles in the config
+            if !visited.insert(name.clone()) {
+                continue;
             }
+            tree.push((depth, name.clone()));
+            self.walk_dependencies(name, upstream, depth + 1, visited, tree);
         }
     }
 
+    /// Cluster-wide CPU usage with each node weighted by its thread count,
+    /// so a 12-thread node counts three times as much as a 4-thread one
+    pub fn cluster_cpu_weighted(&self) -> f64 {
+        let total_threads: u32 = self.nodes.values().map(|node| node.cpu_threads).sum();
+        if total_threads == 0 {
+            return 0.0;
+        }
+
+        let weighted: f64 = self.nodes
+            .values()
+            .map(|node| node.cpu_usage * node.cpu_threads as f64)
+            .sum();
+        weighted / total_threads as f64
+    }
+
+    pub fn selected_node_name(&self) -> Option<String> {
+        self.sorted_node_names().into_iter().nth(self.selected_node_index)
+    }
+
+    pub fn selected_service_name(&self) -> Option<String> {
+        self.filtered_service_names().into_iter().nth(self.selected_service_index)
+    }
+
+    /// Node the detail panels show: the pinned one, else the selected one
+    pub fn detail_node_name(&self) -> Option<String> {
+        self.pinned_detail
+            .clone()
+            .filter(|name| self.nodes.contains_key(name))
+            .or_else(|| self.selected_node_name())
+    }
+
+    /// Service the detail panels show: the pinned one, else the selected one
+    pub fn detail_service_name(&self) -> Option<String> {
+        self.pinned_detail
+            .clone()
+            .filter(|name| self.services.contains_key(name))
+            .or_else(|| self.selected_service_name())
+    }
+
+    /// Freeze or resume the metrics. Updates already in flight are held back
+    /// until resuming.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.set_status_message(if self.paused { "Paused (p to resume)" } else { "Resumed" });
+    }
+
+    /// Pin the detail panels to the selected item of the focused table, or
+    /// unpin them if that item is already pinned
+    pub fn toggle_pin(&mut self) {
+        let selected = match self.active_panel {
+            ActivePanel::Nodes => self.selected_node_name(),
+            ActivePanel::Services => self.selected_service_name(),
+        };
+        if selected.is_none() || self.pinned_detail == selected {
+            self.pinned_detail = None;
+            self.set_status_message("Details unpinned");
+        } else {
+            self.set_status_message(format!("Details pinned to {} (P to unpin)", selected.as_deref().unwrap_or_default()));
+            self.pinned_detail = selected;
+        }
+    }
+
+    pub fn toggle_diagnostics(&mut self) {
+        self.show_diagnostics = !self.show_diagnostics;
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+    }
+
+    /// Open the popup for the node selected in the nodes table, or close it
+    pub fn toggle_node_detail(&mut self) {
+        self.show_node_detail = !self.show_node_detail
+            && self.active_panel == ActivePanel::Nodes
+            && self.selected_node_name().is_some();
+    }
+
+    /// Start typing a services table filter, from the one applied now
+    pub fn start_service_filter(&mut self) {
+        self.service_filter_input = Some(self.filter.selected_service.clone().unwrap_or_default());
+    }
+
+    /// Handle a key while the services filter is being typed: Enter shows
+    /// only services whose name contains it (empty shows all again), Esc
+    /// keeps the previous filter
+    pub fn service_filter_key(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+        let Some(text) = &mut self.service_filter_input else {
+            return;
+        };
+        match key {
+            KeyCode::Char(c) => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Enter => {
+                let selected = self.selected_service_name();
+                let text = self.service_filter_input.take().unwrap_or_default();
+                self.filter.selected_service = (!text.is_empty()).then_some(text);
+                self.reselect_service(selected);
+            }
+            KeyCode::Esc => self.service_filter_input = None,
+            _ => {}
+        }
+    }
+
+    /// Start editing the note of the service in the detail panel
+    pub fn start_note_edit(&mut self) {
+        let Some(service) = self.detail_service_name() else {
+            self.set_status_message("No service selected to annotate");
+            return;
+        };
+        let draft = self.notes.get(&service).unwrap_or_default().to_string();
+        self.note_editing = Some((service, draft));
+    }
+
+    /// Handle a key while a note is being typed: Enter saves it (an empty
+    /// note removes it), Esc discards the edit
+    pub fn note_key(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+        let Some((service, draft)) = &mut self.note_editing else {
+            return;
+        };
+        match key {
+            KeyCode::Char(c) => draft.push(c),
+            KeyCode::Backspace => {
+                draft.pop();
+            }
+            KeyCode::Enter => {
+                self.notes.set(service, draft);
+                let service = service.clone();
+                self.note_editing = None;
+                match self.notes.save() {
+                    Ok(_) => self.set_status_message(format!("Note saved for {}", service)),
+                    Err(e) => self.set_status_message(format!("{:#}", e)),
+                }
+            }
+            KeyCode::Esc => self.note_editing = None,
+            _ => {}
+        }
+    }
+
+    /// Handle a key while the scratchpad is open, running the query Enter submits
+    pub fn scratchpad_key(&mut self, key: crossterm::event::KeyCode) {
+        if let Some(query) = self.scratchpad.key(key) {
+            self.run_scratchpad_query(query);
+        }
+    }
+
+    /// Run `query` on a spawned task with a client of its own, since the
+    /// shared one may be away on a Prometheus update
+    fn run_scratchpad_query(&mut self, query: String) {
+        let client = match Self::prometheus_client(&self.config) {
+            Ok(client) => client,
+            Err(e) => {
+                self.scratchpad.result = Some((query, Err(format!("{:#}", e))));
+                return;
+            }
+        };
+        let (sender, receiver) = oneshot::channel();
+        let task_query = query.clone();
+        tokio::spawn(async move {
+            let result = client.scratchpad_query(&task_query).await.map_err(|e| format!("{:#}", e));
+            let _ = sender.send(result);
+        });
+        self.scratchpad.start(query, receiver);
+    }
+
     // Node navigation
     pub fn next_node(&mut self) {
-        let node_count = self.nodes.len();
+        let node_count = self.sorted_node_names().len();
         if node_count > 0 {
             self.selected_node_index = (self.selected_node_index + 1) % node_count;
+            self.follow_selection(ActivePanel::Nodes);
         }
     }
 
     pub fn previous_node(&mut self) {
-        let node_count = self.nodes.len();
+        let node_count = self.sorted_node_names().len();
         if node_count > 0 {
             self.selected_node_index = if self.selected_node_index == 0 {
                 node_count - 1
             } else {
                 self.selected_node_index - 1
             };
+            self.follow_selection(ActivePanel::Nodes);
         }
     }
 
     // Service navigation
     pub fn next_service(&mut self) {
-        let service_count = self.services.len();
+        let service_count = self.filtered_service_names().len();
         if service_count > 0 {
             self.selected_service_index = (self.selected_service_index + 1) % service_count;
+            self.follow_selection(ActivePanel::Services);
         }
     }
 
     pub fn previous_service(&mut self) {
-        let service_count = self.services.len();
+        let service_count = self.filtered_service_names().len();
         if service_count > 0 {
             self.selected_service_index = if self.selected_service_index == 0 {
                 service_count - 1
             } else {
                 self.selected_service_index - 1
             };
+            self.follow_selection(ActivePanel::Services);
+        }
+    }
+
+    /// With `focus_follows_selection`, focus the panel whose selection just moved
+    fn follow_selection(&mut self, panel: ActivePanel) {
+        if self.config.ui.focus_follows_selection {
+            self.active_panel = panel;
+        }
+    }
+
+    /// Table row under a terminal cell, as (panel, row index)
+    fn table_row_at(&self, column: u16, row: u16) -> Option<(ActivePanel, usize)> {
+        let tables = [
+            (ActivePanel::Nodes, self.nodes_table_area.get(), self.nodes_table_offset.get()),
+            (ActivePanel::Services, self.services_table_area.get(), self.services_table_offset.get()),
+        ];
+        for (panel, area, offset) in tables {
+            // Rows start below the top border and the header
+            let first_row = area.y + 2;
+            let inside_x = column > area.x && column + 1 < area.right();
+            let inside_y = row >= first_row && row + 1 < area.bottom();
+            if inside_x && inside_y {
+                let table_row = offset + (row - first_row) as usize;
+                if panel == ActivePanel::Nodes {
+                    // Group headings aren't nodes; count only the node rows before this one
+                    let rows = self.node_rows();
+                    return match rows.get(table_row) {
+                        Some(NodeRow::Node(_)) => {
+                            let index = rows[..table_row].iter().filter(|row| matches!(row, NodeRow::Node(_))).count();
+                            Some((panel, index))
+                        }
+                        _ => None,
+                    };
+                }
+                return Some((panel, table_row));
+            }
+        }
+        None
+    }
+
+    // Mouse handling
+    pub fn on_mouse_move(&mut self, column: u16, row: u16) {
+        if !self.config.ui.focus_follows_selection {
+            return;
+        }
+        self.hovered_row = self.table_row_at(column, row);
+        if let Some((panel, _)) = &self.hovered_row {
+            self.active_panel = panel.clone();
+        }
+    }
+
+    pub fn on_mouse_click(&mut self, column: u16, row: u16) {
+        match self.table_row_at(column, row) {
+            Some((ActivePanel::Nodes, index)) if index < self.sorted_node_names().len() => {
+                self.selected_node_index = index;
+                self.follow_selection(ActivePanel::Nodes);
+            }
+            Some((ActivePanel::Services, index)) if index < self.filtered_service_names().len() => {
+                self.selected_service_index = index;
+                self.follow_selection(ActivePanel::Services);
+            }
+            _ => {}
+        }
+    }
+
+    /// Nodes (in table order) then services matching the unified search,
+    /// compared case-insensitively against the name, type and namespace
+    pub fn unified_items(&self) -> Vec<UnifiedItem> {
+        let query = self.unified_query.to_lowercase();
+        let nodes = self.sorted_node_names()
+            .into_iter()
+            .filter(|name| query.is_empty() || name.to_lowercase().contains(&query) || "node".contains(&query))
+            .map(UnifiedItem::Node);
+        let services = self.filtered_service_names()
+            .into_iter()
+            .filter(|name| {
+                query.is_empty()
+                    || name.to_lowercase().contains(&query)
+                    || "service".contains(&query)
+                    || self.services[name].namespace.to_lowercase().contains(&query)
+            })
+            .map(UnifiedItem::Service);
+        nodes.chain(services).collect()
+    }
+
+    pub fn toggle_unified_view(&mut self) {
+        self.unified_view = !self.unified_view;
+        self.unified_typing = false;
+        self.unified_index = 0;
+    }
+
+    /// Start typing a search for the unified list
+    pub fn start_unified_search(&mut self) {
+        self.unified_typing = true;
+    }
+
+    /// Handle a key while the unified search is being typed: Enter keeps the
+    /// search, Esc clears it
+    pub fn unified_search_key(&mut self, key: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+        match key {
+            KeyCode::Char(c) => self.unified_query.push(c),
+            KeyCode::Backspace => {
+                self.unified_query.pop();
+            }
+            KeyCode::Enter => self.unified_typing = false,
+            KeyCode::Esc => {
+                self.unified_query.clear();
+                self.unified_typing = false;
+            }
+            _ => return,
+        }
+        self.unified_index = 0;
+        self.sync_unified_selection();
+    }
+
+    fn move_unified_selection(&mut self, forward: bool) {
+        let count = self.unified_items().len();
+        if count == 0 {
+            return;
+        }
+        self.unified_index = if forward { (self.unified_index + 1) % count } else { (self.unified_index + count - 1) % count };
+        self.sync_unified_selection();
+    }
+
+    /// Select the highlighted entry in its own table too, so the split view
+    /// opens on it
+    fn sync_unified_selection(&mut self) {
+        match self.unified_items().get(self.unified_index) {
+            Some(UnifiedItem::Node(name)) => {
+                self.reselect_node(Some(name.clone()));
+                self.active_panel = ActivePanel::Nodes;
+            }
+            Some(UnifiedItem::Service(name)) => {
+                self.reselect_service(Some(name.clone()));
+                self.active_panel = ActivePanel::Services;
+            }
+            None => {}
         }
     }
 
@@ -313,6 +1866,10 @@ impl App {
 
     // Navigation methods for active panel
     pub fn navigate_up(&mut self) {
+        if self.unified_view {
+            self.move_unified_selection(false);
+            return;
+        }
         match self.active_panel {
             ActivePanel::Nodes => self.previous_node(),
             ActivePanel::Services => self.previous_service(),
@@ -320,80 +1877,542 @@ impl App {
     }
 
     pub fn navigate_down(&mut self) {
+        if self.unified_view {
+            self.move_unified_selection(true);
+            return;
+        }
         match self.active_panel {
             ActivePanel::Nodes => self.next_node(),
             ActivePanel::Services => self.next_service(),
         }
     }
 
+    /// Index of the current tab in the configured `ui.tabs` order
+    pub fn tab_index(&self) -> usize {
+        self.config.ui.tabs.iter().position(|&tab| tab == self.current_tab).unwrap_or(0)
+    }
+
     pub fn next_tab(&mut self) {
-        let tabs = [CurrentTab::Overview, CurrentTab::Nodes, CurrentTab::Services, CurrentTab::Compare];
-        let current_pos = tabs.iter().position(|&t| t == self.current_tab).unwrap_or(0);
-        self.current_tab = tabs[(current_pos + 1) % tabs.len()];
+        let tabs = &self.config.ui.tabs;
+        self.set_tab(tabs[(self.tab_index() + 1) % tabs.len()]);
     }
 
     pub fn previous_tab(&mut self) {
-        let tabs = [CurrentTab::Overview, CurrentTab::Nodes, CurrentTab::Services, CurrentTab::Compare];
-        let current_pos = tabs.iter().position(|&t| t == self.current_tab).unwrap_or(0);
-        self.current_tab = tabs[(current_pos + tabs.len() - 1) % tabs.len()];
+        let tabs = &self.config.ui.tabs;
+        self.set_tab(tabs[(self.tab_index() + tabs.len() - 1) % tabs.len()]);
+    }
+
+    /// Switch to the tab at `index` in the tab bar, if there is one
+    pub fn select_tab(&mut self, index: usize) {
+        if let Some(&tab) = self.config.ui.tabs.get(index) {
+            self.set_tab(tab);
+        }
+    }
+
+    /// Single-table tabs also move keyboard focus to their table
+    fn set_tab(&mut self, tab: CurrentTab) {
+        self.current_tab = tab;
+        match tab {
+            CurrentTab::Nodes => self.active_panel = ActivePanel::Nodes,
+            CurrentTab::Services => self.active_panel = ActivePanel::Services,
+            CurrentTab::Overview | CurrentTab::Compare => {}
+        }
     }
 
     pub fn toggle_filter(&mut self) {
         self.filter.enabled = !self.filter.enabled;
     }
 
+    /// Add or remove the selected node or service (per the focused table)
+    /// from the Compare tab
     pub fn toggle_selection(&mut self) {
-        let items = match self.current_tab {
-            CurrentTab::Nodes => self.nodes.keys().cloned().collect(),
-            CurrentTab::Services => self.services.keys().cloned().collect(),
-            _ => vec![],
+        let item = match self.active_panel {
+            ActivePanel::Nodes => self.selected_node_name(),
+            ActivePanel::Services => self.selected_service_name(),
         };
 
-        if !items.is_empty() && self.selected_item_index < items.len() {
-            let item = &items[self.selected_item_index];
-            if let Some(pos) = self.selected_items.iter().position(|x| x == item) {
+        if let Some(item) = item {
+            if let Some(pos) = self.selected_items.iter().position(|x| *x == item) {
                 self.selected_items.remove(pos);
             } else {
-                self.selected_items.push(item.clone());
+                self.selected_items.push(item);
+            }
+        }
+    }
+
+    pub fn set_status_message(&mut self, message: impl Into<String>) {
+        self.status_message = Some((message.into(), Instant::now()));
+    }
+
+    /// Status bar message, if one was set recently
+    pub fn current_status_message(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, set_at)| set_at.elapsed() < STATUS_MESSAGE_TTL)
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Delay before the next reconnection attempt, doubling from the query interval
+    fn reconnect_backoff(&self) -> Duration {
+        let base = Duration::from_secs(self.config.prometheus.query_interval_secs.max(1));
+        let exponent = self.reconnect_attempt.saturating_sub(1).min(16);
+        base.saturating_mul(1 << exponent).min(MAX_RECONNECT_BACKOFF)
+    }
+
+    /// Time left until the next reconnection attempt
+    pub fn retry_in(&self) -> Option<Duration> {
+        self.next_retry_at.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    fn record_error(&mut self, error: String) {
+        self.recent_errors.push(error);
+        if self.recent_errors.len() > MAX_RECENT_ERRORS {
+            self.recent_errors.remove(0);
+        }
+    }
+
+    /// Write a redacted snapshot of the current state to the config directory for bug reports
+    pub fn dump_state(&self) -> anyhow::Result<PathBuf> {
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let snapshot = StateSnapshot {
+            created_at,
+            version: env!("CARGO_PKG_VERSION"),
+            config: self.config.redacted(),
+            connection_status: &self.connection_status,
+            theme: self.current_theme.name(),
+            active_panel: format!("{:?}", self.active_panel),
+            selected_node: self.selected_node_name(),
+            selected_service: self.selected_service_name(),
+            nodes: &self.nodes,
+            services: &self.services,
+            recent_errors: &self.recent_errors,
+        };
+
+        let dir = Config::config_dir()?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        let path = dir.join(format!("state-dump-{}.json", created_at));
+        let content = serde_json::to_string_pretty(&snapshot).context("Failed to serialize state")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write state dump: {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Write the node and service tables to a self-contained HTML file in the
+    /// config directory, returning its path
+    pub fn export_html(&self) -> anyhow::Result<PathBuf> {
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let html = crate::html_export::render(self, created_at);
+
+        let dir = Config::config_dir()?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        let path = dir.join(format!("snapshot-{}.html", created_at));
+        std::fs::write(&path, html)
+            .with_context(|| format!("Failed to write HTML snapshot: {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Write a rendered frame's text (see `ui::buffer_text`) to
+    /// `~/.monitorium/frame-<unix time>.txt`, for pasting into chat
+    pub fn export_frame_text(&self, text: &str) -> anyhow::Result<PathBuf> {
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+        let dir = Config::config_dir()?;
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        let path = dir.join(format!("frame-{}.txt", created_at));
+        std::fs::write(&path, format!("{}\n", text))
+            .with_context(|| format!("Failed to write frame dump: {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    /// Save the current metrics as the named baseline, replacing any earlier one
+    pub fn save_baseline(&mut self) -> anyhow::Result<PathBuf> {
+        let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let baseline = Baseline::capture(&self.baseline_name, created_at, &self.nodes, &self.services);
+        let path = baseline.save()?;
+        self.baseline = Some(baseline);
+        Ok(path)
+    }
+
+    /// Show or hide deltas against the baseline, loading it from disk if it
+    /// was saved by an earlier run
+    pub fn toggle_baseline(&mut self) {
+        if self.show_baseline {
+            self.show_baseline = false;
+            return;
+        }
+        if self.baseline.is_none() {
+            match Baseline::load(&self.baseline_name) {
+                Ok(baseline) => self.baseline = Some(baseline),
+                Err(e) => {
+                    self.set_status_message(format!("{:#}", e));
+                    return;
+                }
             }
         }
+        self.show_baseline = true;
+    }
+
+    /// The baseline deltas are shown against, if the comparison is on
+    pub fn active_baseline(&self) -> Option<&Baseline> {
+        self.baseline.as_ref().filter(|_| self.show_baseline)
+    }
+
+    pub fn toggle_display_mode(&mut self) {
+        self.display_mode = match self.display_mode {
+            DisplayMode::Percent => DisplayMode::Absolute,
+            DisplayMode::Absolute => DisplayMode::Percent,
+        };
+    }
+
+    /// Sort the nodes table by the next column, keeping the selected node selected
+    pub fn cycle_node_sort(&mut self) {
+        let selected = self.selected_node_name();
+        self.node_sort = self.node_sort.next();
+        self.reselect_node(selected);
+    }
+
+    pub fn toggle_node_sort_direction(&mut self) {
+        let selected = self.selected_node_name();
+        self.node_sort_desc = !self.node_sort_desc;
+        self.reselect_node(selected);
+    }
+
+    /// Sort the services table by the next column, keeping the selected service selected
+    pub fn cycle_service_sort(&mut self) {
+        let selected = self.selected_service_name();
+        self.service_sort = self.service_sort.next();
+        self.reselect_service(selected);
+    }
+
+    pub fn toggle_service_sort_direction(&mut self) {
+        let selected = self.selected_service_name();
+        self.service_sort_desc = !self.service_sort_desc;
+        self.reselect_service(selected);
+    }
+
+    fn reselect_node(&mut self, name: Option<String>) {
+        if let Some(index) = name.and_then(|name| self.sorted_node_names().iter().position(|n| *n == name)) {
+            self.selected_node_index = index;
+        }
+    }
+
+    /// Select `name` in the services table, or keep the index within the
+    /// table if it's no longer listed
+    fn reselect_service(&mut self, name: Option<String>) {
+        let names = self.filtered_service_names();
+        match name.and_then(|name| names.iter().position(|n| *n == name)) {
+            Some(index) => self.selected_service_index = index,
+            None => self.selected_service_index = self.selected_service_index.min(names.len().saturating_sub(1)),
+        }
+    }
+
+    pub fn toggle_legend(&mut self) {
+        self.show_legend = !self.show_legend;
+    }
+
+    pub fn toggle_show_queries(&mut self) {
+        self.show_queries = !self.show_queries;
+    }
+
+    /// Undo runtime view tweaks (sort, filter, selections, display toggles),
+    /// going back to the configured defaults. The theme is kept.
+    pub fn reset_view_state(&mut self) {
+        let selected = self.selected_node_name();
+        let selected_service = self.selected_service_name();
+        self.node_sort = NodeSortKey::Name;
+        self.node_sort_desc = false;
+        self.service_sort = ServiceSortKey::Name;
+        self.service_sort_desc = false;
+        self.filter = FilterState::new();
+        self.selected_items.clear();
+        self.display_mode = DisplayMode::Percent;
+        self.show_legend = false;
+        self.show_queries = self.config.ui.show_queries;
+        self.show_baseline = false;
+        self.pinned_detail = None;
+        self.unified_view = false;
+        self.unified_query.clear();
+        self.unified_typing = false;
+        self.unified_index = 0;
+        self.service_filter_input = None;
+        self.show_diagnostics = false;
+        if self.graph_window.take().is_some() {
+            self.node_history.clear();
+            self.service_history.clear();
+            self.memory_history.clear();
+            self.service_memory_history.clear();
+        }
+        self.selected_node_index = 0;
+        self.reselect_node(selected);
+        self.reselect_service(selected_service);
+        self.set_status_message("View reset to defaults");
+    }
+
+    /// Current selection, sort and filters, to restore on the next launch
+    pub fn view_state(&self) -> ViewState {
+        ViewState {
+            current_tab: self.current_tab,
+            active_panel: self.active_panel.clone(),
+            selected_node: self.selected_node_name(),
+            selected_service: self.selected_service_name(),
+            node_sort: self.node_sort,
+            node_sort_desc: self.node_sort_desc,
+            service_sort: self.service_sort,
+            service_sort_desc: self.service_sort_desc,
+            filter: self.filter.clone(),
+            display_mode: self.display_mode,
+            selected_items: self.selected_items.clone(),
+        }
+    }
+
+    /// Apply a saved view state; a tab that's no longer configured, or a node
+    /// or service that's gone, leaves the default in place
+    pub fn restore_view_state(&mut self, state: ViewState) {
+        if self.config.ui.tabs.contains(&state.current_tab) {
+            self.set_tab(state.current_tab);
+        }
+        self.active_panel = state.active_panel;
+        self.node_sort = state.node_sort;
+        self.node_sort_desc = state.node_sort_desc;
+        self.service_sort = state.service_sort;
+        self.service_sort_desc = state.service_sort_desc;
+        self.filter = state.filter;
+        self.display_mode = state.display_mode;
+        self.selected_items = state.selected_items;
+        self.selected_node_index = 0;
+        self.reselect_node(state.selected_node);
+        self.reselect_service(state.selected_service);
+    }
+
+    /// Save the view state under `general.cluster_name` if `ui.remember_view` is on
+    pub fn save_view_state(&self) -> anyhow::Result<()> {
+        if self.config.ui.remember_view {
+            self.view_state().save(&self.config.general.cluster_name)?;
+        }
+        Ok(())
+    }
+
+    /// Switch to the light or dark theme of `ui.auto_theme` when the local
+    /// time crosses into the other period
+    fn apply_auto_theme(&mut self) {
+        let Some(auto_theme) = &self.config.ui.auto_theme else {
+            return;
+        };
+        let light = auto_theme.is_light_at(crate::clock::local_minutes_of_day());
+        if self.auto_theme_light == Some(light) {
+            return;
+        }
+        let name = if light { &auto_theme.light } else { &auto_theme.dark };
+        if let Some(theme) = Theme::from_name(name) {
+            self.set_theme(theme);
+        }
+        self.auto_theme_light = Some(light);
+    }
+
+    /// Switch to `theme`, checking its text colors against `ui.min_contrast`
+    fn set_theme(&mut self, theme: Theme) {
+        self.current_theme = theme;
+        self.theme_colors = ThemeColors::for_terminal(theme, self.truecolor);
+        if let Some(colors) = &self.config.ui.colors {
+            self.theme_colors.apply_overrides(colors);
+        }
+        let Some(min_ratio) = self.config.ui.min_contrast else {
+            return;
+        };
+        let adjust = self.config.ui.adjust_contrast;
+        let low = self.theme_colors.enforce_contrast(min_ratio, adjust);
+        if !low.is_empty() {
+            self.set_status_message(format!(
+                "{} is below {:.1}:1 contrast: {}{}",
+                theme.name(),
+                min_ratio,
+                low.join(", "),
+                if adjust { " (adjusted)" } else { "" }
+            ));
+        }
     }
 
     // Theme switching methods
     pub fn next_theme(&mut self) {
-        self.current_theme = self.current_theme.next();
-        self.theme_colors = ThemeColors::from_theme(self.current_theme);
+        self.set_theme(self.current_theme.next());
     }
 
     pub fn previous_theme(&mut self) {
-        self.current_theme = self.current_theme.previous();
-        self.theme_colors = ThemeColors::from_theme(self.current_theme);
+        self.set_theme(self.current_theme.previous());
     }
+}
 
-    pub fn get_filtered_items(&self) -> Vec<String> {
-        match self.current_tab {
-            CurrentTab::Nodes => {
-                let mut items: Vec<String> = self.nodes.keys().cloned().collect();
-                if let Some(node_filter) = &self.filter.selected_node {
-                    items.retain(|name| name.contains(node_filter));
-                }
-                items
-            }
-            CurrentTab::Services => {
-                let mut items: Vec<String> = self.services.keys().cloned().collect();
-                if let Some(namespace_filter) = &self.filter.selected_namespace {
-                    items.retain(|name| {
-                        self.services.get(name)
-                            .map(|s| s.namespace.contains(namespace_filter))
-                            .unwrap_or(false)
-                    });
-                }
-                if let Some(service_filter) = &self.filter.selected_service {
-                    items.retain(|name| name.contains(service_filter));
-                }
-                items
-            }
-            _ => vec![],
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_interval_never_reaches_zero() {
+        assert_eq!(history_interval_ticks(0), 1000);
+        assert_eq!(history_interval_ticks(1), 1000);
+        assert_eq!(history_interval_ticks(250), 4);
+        assert_eq!(history_interval_ticks(1000), 1);
+        assert_eq!(history_interval_ticks(1001), 1);
+        assert_eq!(history_interval_ticks(5000), 1);
+    }
+
+    #[test]
+    fn blank_outage_clears_everything_a_poll_sets() {
+        let mut config = Config::default();
+        config.prometheus.on_total_failure = TotalFailurePolicy::Blank;
+        let mut app = App::new_mock(config);
+        app.has_real_data = true;
+
+        app.on_data_unavailable();
+        assert!(app.nodes.values().all(|node| node.uptime == 0 && node.swap_usage == 0.0 && node.load_avg == [0.0; 3]));
+        assert!(app.services.values().all(|service| service.image == "unknown" && service.age == 0 && service.status == "Unknown"));
+    }
+
+    #[test]
+    fn service_selection_follows_the_filtered_table() {
+        let mut app = App::new_mock(Config::default());
+        let names = app.filtered_service_names();
+        app.selected_service_index = names.len() - 1;
+        let selected = app.selected_service_name();
+
+        // The selected service leaves the namespace the table lists
+        app.services.get_mut(names.last().unwrap()).unwrap().namespace = "other".to_string();
+        app.reselect_service(selected);
+        assert_eq!(app.selected_service_index, names.len() - 2);
+        app.next_service();
+        assert_eq!(app.selected_service_index, 0);
+
+        app.reselect_service(Some(names[1].clone()));
+        assert_eq!(app.selected_service_name().as_ref(), Some(&names[1]));
+    }
+
+    #[test]
+    fn history_keeps_the_newest_samples_in_order() {
+        let mut history = VecDeque::new();
+        for value in 1..=8 {
+            push_sample(&mut history, value as f64, 5);
         }
+        assert_eq!(history, [4.0, 5.0, 6.0, 7.0, 8.0]);
+
+        // A lower retention (e.g. after a config reload) trims on the next sample
+        push_sample(&mut history, 9.0, 3);
+        assert_eq!(history, [7.0, 8.0, 9.0]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn node_sort_keeps_the_selected_node() {
+        let mut app = App::new_mock(Config::default());
+        app.node_sort = NodeSortKey::Memory;
+        app.node_sort_desc = true;
+        let names = app.sorted_node_names();
+        assert!(app.nodes[&names[0]].memory_usage >= app.nodes[&names[1]].memory_usage);
+
+        app.selected_node_index = 1;
+        let selected = app.selected_node_name();
+        app.toggle_node_sort_direction();
+        assert_eq!(app.selected_node_index, 0);
+        assert_eq!(app.selected_node_name(), selected);
+        app.cycle_node_sort();
+        assert_eq!(app.node_sort, NodeSortKey::Gpu);
+        assert_eq!(app.selected_node_name(), selected);
+    }
+
+    #[test]
+    fn service_selection_survives_a_reordering_update() {
+        let mut app = App::new_mock(Config::default());
+        app.service_sort = ServiceSortKey::Cpu;
+        app.service_sort_desc = true;
+        let names = app.filtered_service_names();
+        app.selected_service_index = names.len() - 1;
+        let selected = app.selected_service_name().unwrap();
+
+        // The idlest service becomes the busiest and moves to the top
+        app.keeping_selection(|app| app.services.get_mut(&selected).unwrap().cpu_usage = 99.0);
+        assert_eq!(app.selected_service_index, 0);
+        assert_eq!(app.selected_service_name(), Some(selected));
+    }
+
+    #[test]
+    fn typed_filter_limits_the_services_table() {
+        use crossterm::event::KeyCode;
+        let mut app = App::new_mock(Config::default());
+        let all = app.filtered_service_names().len();
+        app.start_service_filter();
+        for key in [KeyCode::Char('g'), KeyCode::Char('r'), KeyCode::Char('a'), KeyCode::Char('x'), KeyCode::Backspace, KeyCode::Enter] {
+            app.service_filter_key(key);
+        }
+        assert_eq!(app.filter.selected_service.as_deref(), Some("gra"));
+        assert_eq!(app.filtered_service_names(), ["grafana-0"]);
+
+        // Esc keeps the applied filter; an empty one shows everything again
+        app.start_service_filter();
+        app.service_filter_key(KeyCode::Backspace);
+        app.service_filter_key(KeyCode::Esc);
+        assert_eq!(app.filter.selected_service.as_deref(), Some("gra"));
+        app.start_service_filter();
+        for _ in 0..3 {
+            app.service_filter_key(KeyCode::Backspace);
+        }
+        app.service_filter_key(KeyCode::Enter);
+        assert_eq!(app.filtered_service_names().len(), all);
+    }
+
+    #[test]
+    fn slow_refresh_still_records_history() {
+        let mut config = Config::default();
+        config.ui.refresh_rate_ms = 2000;
+        let mut app = App::new_mock(config);
+        app.on_tick();
+        app.on_tick();
+        assert!(app.node_history.values().all(|history| history.len() == 2));
+        for (name, node) in &app.nodes {
+            assert_eq!(app.memory_history[name].back(), Some(&node.memory_usage));
+        }
+        assert!(app.service_memory_history.values().all(|history| history.len() == 2));
+    }
+
+    #[test]
+    fn view_state_survives_a_round_trip() {
+        let mut app = App::new_mock(Config::default());
+        app.set_tab(CurrentTab::Services);
+        app.node_sort = NodeSortKey::Gpu;
+        app.node_sort_desc = true;
+        app.service_sort = ServiceSortKey::Latency;
+        app.filter.enabled = true;
+        app.selected_service_index = 1;
+        let saved = serde_json::to_string(&app.view_state()).unwrap();
+
+        let mut restored = App::new_mock(Config::default());
+        restored.restore_view_state(serde_json::from_str(&saved).unwrap());
+        assert_eq!(restored.current_tab, CurrentTab::Services);
+        assert_eq!(restored.active_panel, ActivePanel::Services);
+        assert_eq!(restored.node_sort, NodeSortKey::Gpu);
+        assert!(restored.node_sort_desc && restored.filter.enabled);
+        assert_eq!(restored.service_sort, ServiceSortKey::Latency);
+        assert_eq!(restored.selected_service_name(), app.selected_service_name());
+    }
+
+    #[test]
+    fn critical_service_dominates_health_score() {
+        let mut config = Config::default();
+        let mut app = App::new_mock(config.clone());
+        for service in app.services.values_mut() {
+            service.health_status = "Healthy".to_string();
+        }
+        assert_eq!(app.weighted_health_score(), Some(1.0));
+
+        let name = app.services.keys().next().unwrap().clone();
+        config.health_checks.services[0].name = name.clone();
+        config.health_checks.services[0].critical = true;
+        app.config = config;
+        app.services.get_mut(&name).unwrap().health_status = "Unhealthy".to_string();
+        let score = app.weighted_health_score().unwrap();
+        assert!(score < 0.6, "one critical failure should turn the score red, got {}", score);
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use crate::config::Config;
+use crate::mock_data::{NodeMetrics, ServiceMetrics};
+
+/// Node figures kept in a baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeBaseline {
+    pub cpu_usage: f64,
+    pub memory_usage: f64,
+    pub gpu_usage: f64,
+    pub disk_usage: f64,
+    pub temperature: f64,
+}
+
+/// Service figures kept in a baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceBaseline {
+    pub cpu_usage: f64,
+    pub memory_usage: f64,
+    pub requests_per_sec: f64,
+    pub response_time: f64,
+    pub error_rate: f64,
+}
+
+/// Metrics saved under a name, to compare against after a change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    pub created_at: u64,
+    pub nodes: HashMap<String, NodeBaseline>,
+    pub services: HashMap<String, ServiceBaseline>,
+}
+
+impl Baseline {
+    pub fn capture(
+        name: &str,
+        created_at: u64,
+        nodes: &HashMap<String, NodeMetrics>,
+        services: &HashMap<String, ServiceMetrics>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            created_at,
+            nodes: nodes
+                .iter()
+                .map(|(name, node)| {
+                    (name.clone(), NodeBaseline {
+                        cpu_usage: node.cpu_usage,
+                        memory_usage: node.memory_usage,
+                        gpu_usage: node.gpu_usage,
+                        disk_usage: node.disk_usage,
+                        temperature: node.temperature,
+                    })
+                })
+                .collect(),
+            services: services
+                .iter()
+                .map(|(name, service)| {
+                    (name.clone(), ServiceBaseline {
+                        cpu_usage: service.cpu_usage,
+                        memory_usage: service.memory_usage,
+                        requests_per_sec: service.requests_per_sec,
+                        response_time: service.response_time,
+                        error_rate: service.error_rate,
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    /// `~/.monitorium/baselines/<name>.json`
+    pub fn path(name: &str) -> anyhow::Result<PathBuf> {
+        Ok(Config::config_dir()?.join("baselines").join(format!("{}.json", name)))
+    }
+
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        let path = Self::path(&self.name)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create baseline directory: {}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize baseline")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write baseline: {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn load(name: &str) -> anyhow::Result<Self> {
+        let path = Self::path(name)?;
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("No baseline '{}' at {} (press b to save one)", name, path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse baseline: {}", path.display()))
+    }
+}
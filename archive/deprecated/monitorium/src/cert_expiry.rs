@@ -0,0 +1,98 @@
+//! Expiry dates of the TLS certificates health checks are answered with.
+//!
+//! The certificate is read from the health check's own handshake (reqwest's
+//! `TlsInfo`), so no extra connection is made. The health check client
+//! verifies certificates, though: an expired or otherwise invalid one fails
+//! the handshake before it can be read. The check then fails with the TLS
+//! error and the service keeps the expiry date from its last good check.
+
+/// One DER element as (tag, contents, rest of the input)
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count].iter().fold(0usize, |len, &byte| len << 8 | byte as usize);
+        (len, &rest[count..])
+    };
+    (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+}
+
+/// `notAfter` of a DER X.509 certificate, as Unix time
+pub fn not_after(der: &[u8]) -> Option<u64> {
+    let (_, certificate, _) = der_element(der)?;
+    let (_, mut tbs, _) = der_element(certificate)?;
+    // Optional explicit [0] version
+    if tbs.first() == Some(&0xa0) {
+        tbs = der_element(tbs)?.2;
+    }
+    // serialNumber, signature algorithm, issuer
+    for _ in 0..3 {
+        tbs = der_element(tbs)?.2;
+    }
+    let (_, validity, _) = der_element(tbs)?;
+    let (_, _, validity) = der_element(validity)?;
+    let (tag, time, _) = der_element(validity)?;
+    parse_time(tag, time)
+}
+
+/// UTCTime ("YYMMDDHHMMSSZ") or GeneralizedTime ("YYYYMMDDHHMMSSZ") as Unix time
+fn parse_time(tag: u8, time: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(time).ok()?.strip_suffix('Z')?;
+    let (year, rest) = match tag {
+        // Two-digit years below 50 are 20xx (RFC 5280)
+        0x17 => {
+            let year: i64 = text.get(..2)?.parse().ok()?;
+            (if year < 50 { 2000 + year } else { 1900 + year }, text.get(2..)?)
+        }
+        0x18 => (text.get(..4)?.parse().ok()?, text.get(4..)?),
+        _ => return None,
+    };
+    let field = |at: usize| rest.get(at..at + 2)?.parse::<i64>().ok();
+    let (month, day) = (field(0)?, field(2)?);
+    let secs_of_day = field(4)? * 3600 + field(6)? * 60 + field(8)?;
+
+    // Days-from-civil (Howard Hinnant's algorithm), the inverse of `format_utc`
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    u64::try_from(days * 86_400 + secs_of_day).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(tag: u8, contents: &[u8]) -> Vec<u8> {
+        let mut element = vec![tag, contents.len() as u8];
+        element.extend_from_slice(contents);
+        element
+    }
+
+    #[test]
+    fn reads_not_after_from_certificate() {
+        let validity = [tlv(0x17, b"240101000000Z"), tlv(0x17, b"250315123000Z")].concat();
+        let tbs = [
+            tlv(0xa0, &tlv(0x02, &[2])),
+            tlv(0x02, &[1]),
+            tlv(0x30, &[]),
+            tlv(0x30, &[]),
+            tlv(0x30, &validity),
+        ]
+        .concat();
+        let certificate = tlv(0x30, &tlv(0x30, &tbs));
+
+        let expires = not_after(&certificate).unwrap();
+        assert_eq!(crate::html_export::format_utc(expires), "2025-03-15 12:30 UTC");
+        assert_eq!(parse_time(0x18, b"20491231235959Z"), Some(2_524_607_999));
+        assert_eq!(not_after(&certificate[..10]), None);
+    }
+}
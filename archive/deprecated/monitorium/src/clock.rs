@@ -0,0 +1,31 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Minutes since local midnight (UTC where the local zone can't be read)
+pub fn local_minutes_of_day() -> u32 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    local_minutes(now).unwrap_or(((now % 86_400) / 60) as u32)
+}
+
+#[cfg(unix)]
+fn local_minutes(now: u64) -> Option<u32> {
+    let now = now as libc::time_t;
+    // SAFETY: tm is plain data, and localtime_r only writes through the
+    // pointers it's given, which are valid for the whole call
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        return None;
+    }
+    Some((tm.tm_hour * 60 + tm.tm_min) as u32)
+}
+
+#[cfg(not(unix))]
+fn local_minutes(_now: u64) -> Option<u32> {
+    None
+}
+
+/// "HH:MM" as minutes since midnight
+pub fn parse_time_of_day(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
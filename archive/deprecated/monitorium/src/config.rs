@@ -1,11 +1,14 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use dirs::home_dir;
 
+/// Fewest history points kept; graphs are meaningless below this
+pub const MIN_HISTORY_RETENTION: usize = 10;
+
 /// Main configuration structure for Monitorium
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     /// General application settings
     pub general: GeneralConfig,
@@ -24,6 +27,76 @@ pub struct Config {
 
     /// Logging configuration
     pub logging: LoggingConfig,
+
+    /// Service dependencies: each service mapped to the services it depends on
+    #[serde(default)]
+    pub dependencies: std::collections::HashMap<String, Vec<String>>,
+
+    /// Image each service is expected to run, as a full reference or just a
+    /// tag; the service details highlight a different running image
+    #[serde(default)]
+    pub expected_images: std::collections::HashMap<String, String>,
+
+    /// Client-side alerting
+    #[serde(default, deserialize_with = "deserialize_alerts")]
+    pub alerts: AlertsConfig,
+}
+
+/// Client-side alerting settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    /// Alert rules evaluated locally against the collected metrics
+    #[serde(default)]
+    pub rules: Vec<AlertRuleConfig>,
+
+    /// Node temperature (°C) at or above which a banner overrides the alerts
+    /// panel until it drops again
+    #[serde(default)]
+    pub temp_critical: Option<f64>,
+
+    /// How to signal an alert that starts firing
+    #[serde(default)]
+    pub bell_mode: BellMode,
+}
+
+/// `alerts` as a section, or as just the list of rules
+fn deserialize_alerts<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<AlertsConfig, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Alerts {
+        Rules(Vec<AlertRuleConfig>),
+        Section(AlertsConfig),
+    }
+
+    Ok(match Alerts::deserialize(deserializer)? {
+        Alerts::Rules(rules) => AlertsConfig { rules, ..AlertsConfig::default() },
+        Alerts::Section(alerts) => alerts,
+    })
+}
+
+/// Client-side alert rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRuleConfig {
+    /// Name shown in the alerts panel
+    pub name: String,
+
+    /// Whether the rule applies to nodes or services
+    pub scope: AlertScope,
+
+    /// Condition such as "cpu_usage > 90 for 2m"
+    pub rule: String,
+
+    /// Only check this node/service (optional, defaults to all)
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+/// What an alert rule is evaluated against
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertScope {
+    Node,
+    Service,
 }
 
 /// General application settings
@@ -43,6 +116,22 @@ pub struct GeneralConfig {
 
     /// Theme to use (default, dark, light)
     pub theme: String,
+
+    /// Cluster name shown in the header
+    #[serde(default = "default_cluster_name")]
+    pub cluster_name: String,
+
+    /// Namespace whose services are listed
+    #[serde(default = "default_namespace")]
+    pub default_namespace: String,
+}
+
+fn default_cluster_name() -> String {
+    "Homelab Monitoring".to_string()
+}
+
+fn default_namespace() -> String {
+    "homelab".to_string()
 }
 
 /// Prometheus configuration
@@ -63,8 +152,105 @@ pub struct PrometheusConfig {
     /// Custom Prometheus queries for services
     pub service_queries: ServiceQueries,
 
+    /// Query overrides keyed by service name, used instead of
+    /// `service_queries` for that service
+    #[serde(default)]
+    pub service_overrides: std::collections::HashMap<String, ServiceQueryOverrides>,
+
+    /// Separate Prometheus for service queries, when app metrics live in a
+    /// different instance than node metrics (optional, defaults to `url`)
+    #[serde(default)]
+    pub service_prometheus_url: Option<String>,
+
     /// Authentication (optional)
     pub auth: Option<PrometheusAuth>,
+
+    /// File with the URL and credentials, merged over this section on load
+    /// so secrets can stay out of the main config (optional)
+    #[serde(default)]
+    pub credentials_file: Option<String>,
+
+    /// What to do with a metric when a query returns no series for it
+    #[serde(default)]
+    pub on_missing: MissingDataPolicy,
+
+    /// User-Agent sent with queries (optional, defaults to `monitorium/<version>`)
+    #[serde(default)]
+    pub user_agent: Option<String>,
+
+    /// Query for recent Kubernetes events per pod (optional, e.g. from an
+    /// event exporter); enables the Events panel
+    #[serde(default)]
+    pub events_query: Option<String>,
+
+    /// Query for CPU usage per process group and node (optional, e.g. from
+    /// process-exporter); enables the Top Processes panel
+    #[serde(default)]
+    pub process_query: Option<String>,
+
+    /// Tenant sent as `X-Scope-OrgID` to multi-tenant backends
+    /// (Cortex, Mimir, Thanos) (optional)
+    #[serde(default)]
+    pub org_id: Option<String>,
+
+    /// Ask Prometheus for gzip/deflate compressed responses
+    #[serde(default = "default_accept_compression")]
+    pub accept_compression: bool,
+
+    /// What to show when all queries fail mid-session
+    #[serde(default)]
+    pub on_total_failure: TotalFailurePolicy,
+
+    /// Idle keep-alive connections kept open per host (0 disables reuse)
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+
+    /// Seconds an idle connection is kept before it is closed
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+
+    /// Label matchers added to every query, e.g. `env: prod` to scope the
+    /// dashboard to one environment
+    #[serde(default)]
+    pub label_selectors: std::collections::HashMap<String, String>,
+}
+
+fn default_accept_compression() -> bool {
+    true
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    4
+}
+
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+/// Handling of metrics that a query returned no series for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingDataPolicy {
+    /// Keep showing the last known value
+    #[default]
+    Retain,
+    /// Reset the value to zero/unknown
+    Clear,
+    /// Keep the last known value but display it as stale
+    MarkStale,
+}
+
+/// What to show when every query fails after real data has been seen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TotalFailurePolicy {
+    /// Animate mock data (can pass for real numbers during an outage)
+    Mock,
+    /// Keep the last real values, greyed out
+    #[default]
+    Freeze,
+    /// Zero every value and show a "NO DATA" banner
+    Blank,
 }
 
 /// Custom Prometheus queries for node metrics
@@ -85,11 +271,75 @@ pub struct NodeQueries {
     /// Network TX query
     pub network_tx: String,
 
+    /// Network receive errors per second
+    #[serde(default = "default_network_errors_query")]
+    pub network_errors: String,
+
+    /// Network receive drops per second
+    #[serde(default = "default_network_drops_query")]
+    pub network_drops: String,
+
+    /// Swap usage percentage
+    #[serde(default = "default_swap_usage_query")]
+    pub swap_usage: String,
+
     /// Disk usage query
     pub disk_usage: String,
 
     /// Temperature query
     pub temperature: Option<String>,
+
+    /// Seconds since boot
+    #[serde(default = "default_uptime_query")]
+    pub uptime: String,
+
+    /// 1, 5 and 15 minute load averages
+    #[serde(default = "default_load1_query")]
+    pub load1: String,
+    #[serde(default = "default_load5_query")]
+    pub load5: String,
+    #[serde(default = "default_load15_query")]
+    pub load15: String,
+
+    /// How to combine several series for one node, per query name (e.g.
+    /// `disk_usage: max` across mountpoints); unlisted queries use `last`
+    #[serde(default)]
+    pub aggregation: std::collections::HashMap<String, Aggregation>,
+}
+
+/// Names of the `NodeQueries` expressions, as used in `aggregation`
+pub const NODE_QUERY_NAMES: [&str; 14] = [
+    "cpu_usage", "memory_usage", "gpu_usage", "network_rx", "network_tx",
+    "network_errors", "network_drops", "swap_usage", "disk_usage", "temperature",
+    "uptime", "load1", "load5", "load15",
+];
+
+/// Combines the values of several series that map to the same node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregation {
+    Sum,
+    Avg,
+    /// The worst case for usage-style metrics
+    Max,
+    Min,
+    /// The last series returned
+    #[default]
+    Last,
+}
+
+impl Aggregation {
+    /// None when there are no values
+    pub fn apply(self, values: &[f64]) -> Option<f64> {
+        let last = *values.last()?;
+        Some(match self {
+            Self::Sum => values.iter().sum(),
+            Self::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Self::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Last => last,
+        })
+    }
 }
 
 /// Custom Prometheus queries for service metrics
@@ -112,6 +362,30 @@ pub struct ServiceQueries {
 
     /// Error rate query
     pub error_rate: String,
+
+    /// Pod creation time (Unix seconds), shown as the service's age
+    #[serde(default = "default_pod_created_query")]
+    pub pod_created: String,
+
+    /// Running containers with their `image` label, shown in the service details
+    #[serde(default = "default_container_info_query")]
+    pub container_info: String,
+}
+
+/// Per-service replacements for `ServiceQueries` expressions, for services
+/// that expose metrics under different names or labels
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceQueryOverrides {
+    #[serde(default)]
+    pub cpu_usage: Option<String>,
+    #[serde(default)]
+    pub memory_usage: Option<String>,
+    #[serde(default)]
+    pub requests_per_sec: Option<String>,
+    #[serde(default)]
+    pub response_time: Option<String>,
+    #[serde(default)]
+    pub error_rate: Option<String>,
 }
 
 /// Prometheus authentication configuration
@@ -127,6 +401,15 @@ pub struct PrometheusAuth {
     pub bearer_token: Option<String>,
 }
 
+/// Contents of `prometheus.credentials_file`
+#[derive(Debug, Default, Deserialize)]
+struct PrometheusCredentials {
+    url: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+    bearer_token: Option<String>,
+}
+
 /// Health check configuration for services
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HealthCheckConfig {
@@ -142,6 +425,11 @@ pub struct HealthCheckConfig {
     /// Number of consecutive failures before marking as unhealthy
     pub failure_threshold: u32,
 
+    /// Read the TLS certificates of HTTPS endpoints and alert when one
+    /// expires within this many days (optional)
+    #[serde(default)]
+    pub cert_warning_days: Option<u64>,
+
     /// Individual service health check configurations
     pub services: Vec<ServiceHealthCheck>,
 }
@@ -175,6 +463,19 @@ pub struct ServiceHealthCheck {
 
     /// Custom response time threshold in milliseconds
     pub response_time_threshold_ms: Option<u64>,
+
+    /// Show a failing service as "Starting" rather than unhealthy until it
+    /// has been seen for this many seconds (0 disables)
+    #[serde(default)]
+    pub startup_grace_secs: u64,
+
+    /// Count this service's health more in the cluster health score
+    #[serde(default)]
+    pub critical: bool,
+
+    /// Weight in the cluster health score (default 1, or 10 when `critical`)
+    #[serde(default)]
+    pub weight: Option<f64>,
 }
 
 /// Node monitoring configuration
@@ -185,6 +486,43 @@ pub struct NodeConfig {
 
     /// Default values for nodes not explicitly configured
     pub defaults: NodeDefaults,
+
+    /// Node label that picks the detail layout
+    #[serde(default = "default_layout_label")]
+    pub layout_label: String,
+
+    /// Detail layout for each value of `layout_label`
+    #[serde(default = "default_layouts")]
+    pub layouts: std::collections::HashMap<String, NodeLayout>,
+
+    /// Node label (e.g. "location") whose values split the nodes table into
+    /// sections (optional, flat table when unset)
+    #[serde(default)]
+    pub group_by: Option<String>,
+}
+
+/// Panels shown below the resource gauges for the selected node
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeLayout {
+    /// History graphs only
+    #[default]
+    Standard,
+    /// History graphs and GPU panels
+    Compute,
+    /// History graphs and pod density
+    Service,
+}
+
+fn default_layout_label() -> String {
+    "type".to_string()
+}
+
+fn default_layouts() -> std::collections::HashMap<String, NodeLayout> {
+    std::collections::HashMap::from([
+        ("compute".to_string(), NodeLayout::Compute),
+        ("service".to_string(), NodeLayout::Service),
+    ])
 }
 
 /// Individual node configuration
@@ -210,15 +548,26 @@ pub struct NodeDefaults {
     pub display_name: Option<String>,
 
     /// Default temperature unit (C, F)
+    #[serde(default = "default_temperature_unit")]
     pub temperature_unit: String,
 
     /// Default network unit (MB/s, GB/s, etc.)
+    #[serde(default = "default_network_unit")]
     pub network_unit: String,
 
     /// Whether to show GPU metrics
+    #[serde(default)]
     pub show_gpu: bool,
 }
 
+fn default_temperature_unit() -> String {
+    "C".to_string()
+}
+
+fn default_network_unit() -> String {
+    "MB/s".to_string()
+}
+
 /// UI configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UiConfig {
@@ -242,10 +591,239 @@ pub struct UiConfig {
 
     /// Layout configuration
     pub layout: LayoutConfig,
+
+    /// Move focus to the table being navigated or hovered with the mouse
+    #[serde(default)]
+    pub focus_follows_selection: bool,
+
+    /// How the selected table row is highlighted
+    #[serde(default)]
+    pub selection_style: SelectionStyle,
+
+    /// Usage percentages at which metrics turn yellow and red
+    #[serde(default)]
+    pub thresholds: ThresholdConfig,
+
+    /// Append the source metric name to graph and gauge titles
+    #[serde(default)]
+    pub show_queries: bool,
+
+    /// Slow the refresh rate down while the machine runs on battery
+    #[serde(default)]
+    pub battery_saver: bool,
+
+    /// Show large counts as `1.2k`/`3.4M` rather than in full
+    #[serde(default = "default_compact_numbers")]
+    pub compact_numbers: bool,
+
+    /// Briefly highlight the border of tables whose values just changed
+    #[serde(default = "default_flash_updates")]
+    pub flash_updates: bool,
+
+    /// Tabs shown in the tab bar, in order; the first is opened at startup
+    #[serde(default = "default_tabs")]
+    pub tabs: Vec<CurrentTab>,
+
+    /// Whether themes use RGB colors or their 16-color fallback
+    #[serde(default)]
+    pub color_mode: ColorMode,
+
+    /// Switch between a light and a dark theme by local time of day
+    #[serde(default)]
+    pub auto_theme: Option<AutoThemeConfig>,
+
+    /// Save the selection, sort and filters on quit and restore them on the
+    /// next launch, per `general.cluster_name`
+    #[serde(default)]
+    pub remember_view: bool,
+
+    /// Warn when a theme's text is below this WCAG contrast ratio against its
+    /// background, e.g. 4.5 (RGB colors only)
+    #[serde(default)]
+    pub min_contrast: Option<f64>,
+
+    /// Lighten or darken such text until it meets `min_contrast`
+    #[serde(default)]
+    pub adjust_contrast: bool,
+
+    /// Reload and apply the config file whenever it's saved
+    #[serde(default)]
+    pub watch_config: bool,
+
+    /// Processes listed in the Top Processes panel (`prometheus.process_query`)
+    #[serde(default = "default_top_processes")]
+    pub top_processes: usize,
+
+    /// How metric history graphs are drawn
+    #[serde(default)]
+    pub graph_style: GraphStyle,
+
+    /// Decimal and thousands separators of metric values
+    #[serde(default)]
+    pub locale: NumberLocale,
 }
 
-/// Color scheme configuration
+fn default_top_processes() -> usize {
+    5
+}
+
+/// Day and night themes with the local times they start at
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoThemeConfig {
+    pub light: String,
+    pub dark: String,
+    /// "HH:MM" from which the light theme is used
+    #[serde(default = "default_light_after")]
+    pub light_after: String,
+    /// "HH:MM" from which the dark theme is used
+    #[serde(default = "default_dark_after")]
+    pub dark_after: String,
+}
+
+fn default_light_after() -> String {
+    "07:00".to_string()
+}
+
+fn default_dark_after() -> String {
+    "19:00".to_string()
+}
+
+impl AutoThemeConfig {
+    /// Whether the light theme applies at `minutes` past local midnight.
+    /// The light period may wrap past midnight.
+    pub fn is_light_at(&self, minutes: u32) -> bool {
+        let parse = |value: &str| crate::clock::parse_time_of_day(value).unwrap_or(0);
+        let (light_after, dark_after) = (parse(&self.light_after), parse(&self.dark_after));
+        if light_after <= dark_after {
+            (light_after..dark_after).contains(&minutes)
+        } else {
+            minutes >= light_after || minutes < dark_after
+        }
+    }
+}
+
+fn default_tabs() -> Vec<CurrentTab> {
+    vec![CurrentTab::Overview, CurrentTab::Nodes, CurrentTab::Services, CurrentTab::Compare]
+}
+
+fn default_compact_numbers() -> bool {
+    true
+}
+
+fn default_flash_updates() -> bool {
+    true
+}
+
+/// Usage color thresholds, in percent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdConfig {
+    /// Above this a metric is shown as a warning
+    pub warning: f64,
+
+    /// Above this a metric is shown as critical
+    pub critical: f64,
+}
+
+/// A tab of the main view
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrentTab {
+    /// Nodes and services side by side
+    Overview,
+    Nodes,
+    Services,
+    /// History of the items selected with Space
+    Compare,
+}
+
+impl CurrentTab {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Overview => "Overview",
+            Self::Nodes => "Nodes",
+            Self::Services => "Services",
+            Self::Compare => "Compare",
+        }
+    }
+}
+
+/// Signal given when an alert starts firing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BellMode {
+    /// Ring the terminal bell (BEL)
+    Audible,
+    /// Flash the whole screen for one frame
+    Visual,
+    #[default]
+    None,
+}
+
+/// Drawing style of the metric history graphs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphStyle {
+    /// Sparkline bars
+    #[default]
+    Bar,
+    /// A line chart of half-block cells
+    Line,
+    /// A line chart of braille dots, 2x4 per cell for the finest resolution
+    Braille,
+}
+
+/// Number formatting of metric values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberLocale {
+    /// `1234.5`, without grouping
+    #[default]
+    En,
+    /// `1.234,5`
+    De,
+    /// `1 234,5`
+    Fr,
+    /// `1'234.5`
+    Ch,
+}
+
+/// Color depth used for themes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    /// Truecolor when `COLORTERM` advertises it, ANSI otherwise
+    #[default]
+    Auto,
+    Truecolor,
+    /// Each theme's hand-picked 16-color palette
+    Ansi,
+}
+
+impl ColorMode {
+    pub fn truecolor(self) -> bool {
+        match self {
+            Self::Auto => crate::theme::supports_truecolor(),
+            Self::Truecolor => true,
+            Self::Ansi => false,
+        }
+    }
+}
+
+/// Highlight style for the selected table row
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectionStyle {
+    /// The theme's selection foreground/background colors
+    #[default]
+    Theme,
+    /// Reverse video
+    Reverse,
+    /// Underlined and bold
+    Underline,
+}
+
+/// Color scheme configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ColorConfig {
     /// Primary color (hex code)
     pub primary: Option<String>,
@@ -304,19 +882,6 @@ pub struct LoggingConfig {
     pub max_files: u32,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            general: GeneralConfig::default(),
-            prometheus: PrometheusConfig::default(),
-            health_checks: HealthCheckConfig::default(),
-            nodes: NodeConfig::default(),
-            ui: UiConfig::default(),
-            logging: LoggingConfig::default(),
-        }
-    }
-}
-
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
@@ -325,6 +890,8 @@ impl Default for GeneralConfig {
             connection_timeout_secs: 10,
             fullscreen: false,
             theme: "default".to_string(),
+            cluster_name: default_cluster_name(),
+            default_namespace: default_namespace(),
         }
     }
 }
@@ -337,11 +904,53 @@ impl Default for PrometheusConfig {
             query_interval_secs: 5,
             node_queries: NodeQueries::default(),
             service_queries: ServiceQueries::default(),
+            service_overrides: std::collections::HashMap::new(),
+            service_prometheus_url: None,
             auth: None,
+            credentials_file: None,
+            org_id: None,
+            user_agent: None,
+            events_query: None,
+            process_query: None,
+            on_missing: MissingDataPolicy::default(),
+            accept_compression: default_accept_compression(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
+            label_selectors: std::collections::HashMap::new(),
+            on_total_failure: TotalFailurePolicy::default(),
         }
     }
 }
 
+fn default_network_errors_query() -> String {
+    "sum by (instance) (rate(node_network_receive_errs_total[5m]))".to_string()
+}
+
+fn default_network_drops_query() -> String {
+    "sum by (instance) (rate(node_network_receive_drop_total[5m]))".to_string()
+}
+
+fn default_uptime_query() -> String {
+    "node_time_seconds - node_boot_time_seconds".to_string()
+}
+
+fn default_load1_query() -> String {
+    "node_load1".to_string()
+}
+
+fn default_load5_query() -> String {
+    "node_load5".to_string()
+}
+
+fn default_load15_query() -> String {
+    "node_load15".to_string()
+}
+
+fn default_swap_usage_query() -> String {
+    // clamp_min keeps nodes without swap at 0% instead of NaN
+    "(node_memory_SwapTotal_bytes - node_memory_SwapFree_bytes) / clamp_min(node_memory_SwapTotal_bytes, 1) * 100".to_string()
+}
+
 impl Default for NodeQueries {
     fn default() -> Self {
         Self {
@@ -350,12 +959,28 @@ impl Default for NodeQueries {
             gpu_usage: None,
             network_rx: "irate(node_network_receive_bytes_total[5m]) / 1024 / 1024".to_string(),
             network_tx: "irate(node_network_transmit_bytes_total[5m]) / 1024 / 1024".to_string(),
+            network_errors: default_network_errors_query(),
+            network_drops: default_network_drops_query(),
+            swap_usage: default_swap_usage_query(),
             disk_usage: "((1 - (node_filesystem_avail_bytes{mountpoint=\"/\"} / node_filesystem_size_bytes{mountpoint=\"/\"})) * 100)".to_string(),
             temperature: Some("node_hwmon_temp_celsius".to_string()),
+            uptime: default_uptime_query(),
+            load1: default_load1_query(),
+            load5: default_load5_query(),
+            load15: default_load15_query(),
+            aggregation: std::collections::HashMap::new(),
         }
     }
 }
 
+fn default_pod_created_query() -> String {
+    "kube_pod_created".to_string()
+}
+
+fn default_container_info_query() -> String {
+    "kube_pod_container_info".to_string()
+}
+
 impl Default for ServiceQueries {
     fn default() -> Self {
         Self {
@@ -365,6 +990,8 @@ impl Default for ServiceQueries {
             requests_per_sec: "rate(container_http_requests_total[5m])".to_string(),
             response_time: "histogram_quantile(0.95, rate(container_http_request_duration_seconds_bucket[5m])) * 1000".to_string(),
             error_rate: "rate(container_http_requests_total{status=~\"5..\"}[5m]) / rate(container_http_requests_total[5m]) * 100".to_string(),
+            pod_created: default_pod_created_query(),
+            container_info: default_container_info_query(),
         }
     }
 }
@@ -376,6 +1003,7 @@ impl Default for HealthCheckConfig {
             interval_secs: 30,
             timeout_secs: 5,
             failure_threshold: 3,
+            cert_warning_days: None,
             services: vec![
                 ServiceHealthCheck {
                     name: "n8n-0".to_string(),
@@ -387,6 +1015,9 @@ impl Default for HealthCheckConfig {
                     enabled: true,
                     timeout_secs: Some(5),
                     response_time_threshold_ms: Some(1000),
+                    startup_grace_secs: 0,
+                    critical: false,
+                    weight: None,
                 },
                 ServiceHealthCheck {
                     name: "postgres-0".to_string(),
@@ -398,6 +1029,9 @@ impl Default for HealthCheckConfig {
                     enabled: true,
                     timeout_secs: Some(5),
                     response_time_threshold_ms: Some(500),
+                    startup_grace_secs: 0,
+                    critical: false,
+                    weight: None,
                 },
                 ServiceHealthCheck {
                     name: "redis-0".to_string(),
@@ -409,6 +1043,9 @@ impl Default for HealthCheckConfig {
                     enabled: true,
                     timeout_secs: Some(3),
                     response_time_threshold_ms: Some(200),
+                    startup_grace_secs: 0,
+                    critical: false,
+                    weight: None,
                 },
                 ServiceHealthCheck {
                     name: "prometheus-0".to_string(),
@@ -420,6 +1057,9 @@ impl Default for HealthCheckConfig {
                     enabled: true,
                     timeout_secs: Some(5),
                     response_time_threshold_ms: Some(500),
+                    startup_grace_secs: 0,
+                    critical: false,
+                    weight: None,
                 },
                 ServiceHealthCheck {
                     name: "grafana-0".to_string(),
@@ -431,6 +1071,9 @@ impl Default for HealthCheckConfig {
                     enabled: true,
                     timeout_secs: Some(5),
                     response_time_threshold_ms: Some(1000),
+                    startup_grace_secs: 0,
+                    critical: false,
+                    weight: None,
                 },
             ],
         }
@@ -480,6 +1123,9 @@ impl Default for NodeConfig {
                 network_unit: "MB/s".to_string(),
                 show_gpu: false,
             },
+            layout_label: default_layout_label(),
+            layouts: default_layouts(),
+            group_by: None,
         }
     }
 }
@@ -494,6 +1140,32 @@ impl Default for UiConfig {
             max_log_lines: 10,
             colors: None,
             layout: LayoutConfig::default(),
+            focus_follows_selection: false,
+            selection_style: SelectionStyle::default(),
+            thresholds: ThresholdConfig::default(),
+            show_queries: false,
+            battery_saver: false,
+            compact_numbers: default_compact_numbers(),
+            flash_updates: default_flash_updates(),
+            tabs: default_tabs(),
+            color_mode: ColorMode::default(),
+            auto_theme: None,
+            remember_view: false,
+            min_contrast: None,
+            adjust_contrast: false,
+            watch_config: false,
+            top_processes: default_top_processes(),
+            graph_style: GraphStyle::default(),
+            locale: NumberLocale::default(),
+        }
+    }
+}
+
+impl Default for ThresholdConfig {
+    fn default() -> Self {
+        Self {
+            warning: 60.0,
+            critical: 80.0,
         }
     }
 }
@@ -522,28 +1194,68 @@ impl Default for LoggingConfig {
     }
 }
 
+impl PrometheusConfig {
+    /// Host (and port, if any) of the Prometheus URL, for display
+    pub fn host(&self) -> String {
+        match reqwest::Url::parse(&self.url) {
+            Ok(url) => match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_string(),
+                _ => self.url.clone(),
+            },
+            Err(_) => self.url.clone(),
+        }
+    }
+}
+
 impl Config {
-    /// Load configuration from file or create default
-    pub fn load() -> Result<Self> {
+    /// Load the config from the default path, creating a default one if it
+    /// doesn't exist yet; see `load_from`
+    pub fn load() -> Result<(Self, Vec<String>)> {
         let config_path = Self::get_config_path()?;
 
         if config_path.exists() {
-            println!("Loading configuration from: {}", config_path.display());
-            let content = fs::read_to_string(&config_path)
-                .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
-
-            let config: Config = serde_yaml::from_str(&content)
-                .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
-
-            Ok(config)
+            // stderr, so `--dump-config` output stays valid YAML
+            eprintln!("Loading configuration from: {}", config_path.display());
+            Self::load_from(&config_path)
         } else {
-            println!("No configuration file found, creating default at: {}", config_path.display());
+            eprintln!("No configuration file found, creating default at: {}", config_path.display());
             let config = Config::default();
             config.save()?;
-            Ok(config)
+            Ok((config, Vec::new()))
         }
     }
 
+    /// Read the config file at `path` with credentials merged and limits
+    /// clamped, along with warnings about what was adjusted. They're returned
+    /// rather than printed, since a live reload runs while the TUI owns the screen.
+    pub fn load_from(path: &Path) -> Result<(Self, Vec<String>)> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+
+        let mut config: Config = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))?;
+
+        let mut warnings: Vec<String> = config.merge_credentials_file()?.into_iter().collect();
+        warnings.extend(config.clamp_limits());
+
+        Ok((config, warnings))
+    }
+
+    /// Raise values that would leave the dashboard unusable to their minimum,
+    /// returning a warning for each
+    pub fn clamp_limits(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.general.history_retention < MIN_HISTORY_RETENTION {
+            warnings.push(format!(
+                "general.history_retention {} is too small, using {}",
+                self.general.history_retention, MIN_HISTORY_RETENTION
+            ));
+            self.general.history_retention = MIN_HISTORY_RETENTION;
+        }
+        warnings
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
         let config_path = Self::get_config_path()?;
@@ -560,36 +1272,349 @@ impl Config {
         fs::write(&config_path, content)
             .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
 
-        println!("Configuration saved to: {}", config_path.display());
+        // stderr, like `load`, so a first-run `--dump-config` stays valid YAML
+        eprintln!("Configuration saved to: {}", config_path.display());
         Ok(())
     }
 
+    /// Merge `prometheus.credentials_file`, if set, over the Prometheus
+    /// section. Warns when the file is readable by other users.
+    fn merge_credentials_file(&mut self) -> Result<Option<String>> {
+        let Some(path) = self.prometheus.credentials_file.as_deref() else {
+            return Ok(None);
+        };
+        let path = Self::resolve_path(path)?;
+
+        let warning = world_readable_warning(&path);
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read credentials file: {}", path.display()))?;
+        let credentials: PrometheusCredentials = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse credentials file: {}", path.display()))?;
+
+        if let Some(url) = credentials.url {
+            self.prometheus.url = url;
+        }
+        if credentials.username.is_some() || credentials.password.is_some() || credentials.bearer_token.is_some() {
+            let auth = self.prometheus.auth.get_or_insert_with(|| PrometheusAuth {
+                username: String::new(),
+                password: String::new(),
+                bearer_token: None,
+            });
+            if let Some(username) = credentials.username {
+                auth.username = username;
+            }
+            if let Some(password) = credentials.password {
+                auth.password = password;
+            }
+            if credentials.bearer_token.is_some() {
+                auth.bearer_token = credentials.bearer_token;
+            }
+        }
+
+        Ok(warning)
+    }
+
+    /// Expand a leading `~/` and resolve relative paths against the config directory
+    fn resolve_path(path: &str) -> Result<PathBuf> {
+        if let Some(rest) = path.strip_prefix("~/") {
+            let home = home_dir().context("Could not find home directory")?;
+            return Ok(home.join(rest));
+        }
+        let path = PathBuf::from(path);
+        if path.is_absolute() {
+            Ok(path)
+        } else {
+            Ok(Self::config_dir()?.join(path))
+        }
+    }
+
     /// Get the configuration file path
     pub fn get_config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.yaml"))
+    }
+
+    /// Directory holding the config file and any files Monitorium writes
+    pub fn config_dir() -> Result<PathBuf> {
         let home = home_dir().context("Could not find home directory")?;
-        let config_dir = home.join(".monitorium");
-        Ok(config_dir.join("config.yaml"))
+        Ok(home.join(".monitorium"))
     }
 
-    /// Validate configuration
-    pub fn validate(&self) -> Result<()> {
+    /// Copy of the configuration with passwords, tokens and auth headers masked
+    pub fn redacted(&self) -> Config {
+        let mut config = self.clone();
+
+        if let Some(auth) = config.prometheus.auth.as_mut() {
+            auth.password = "***".to_string();
+            if auth.bearer_token.is_some() {
+                auth.bearer_token = Some("***".to_string());
+            }
+        }
+
+        for check in &mut config.health_checks.services {
+            if let Some(headers) = check.headers.as_mut() {
+                for (name, value) in headers.iter_mut() {
+                    if name.eq_ignore_ascii_case("authorization") {
+                        *value = "***".to_string();
+                    }
+                }
+            }
+        }
+
+        config
+    }
+
+    /// Validate configuration, returning warnings about settings that are
+    /// accepted but won't work as configured
+    pub fn validate(&self) -> Result<Vec<String>> {
         // Validate Prometheus URL
         if self.prometheus.url.is_empty() {
             return Err(anyhow::anyhow!("Prometheus URL cannot be empty"));
         }
+        if self.prometheus.service_prometheus_url.as_deref().is_some_and(str::is_empty) {
+            return Err(anyhow::anyhow!("Service Prometheus URL cannot be empty (remove it to use the main URL)"));
+        }
+
+        let is_label_name = |name: &str| {
+            name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        if let Some(label) = self.prometheus.label_selectors.keys().find(|label| !is_label_name(label)) {
+            return Err(anyhow::anyhow!("Invalid label name in prometheus.label_selectors: '{}'", label));
+        }
+
+        if let Some(query) = self.prometheus.node_queries.aggregation.keys().find(|query| !NODE_QUERY_NAMES.contains(&query.as_str())) {
+            return Err(anyhow::anyhow!(
+                "Unknown query '{}' in prometheus.node_queries.aggregation (expected one of: {})",
+                query,
+                NODE_QUERY_NAMES.join(", ")
+            ));
+        }
+
+        if self.alerts.temp_critical.is_some_and(|limit| !limit.is_finite() || limit <= 0.0) {
+            return Err(anyhow::anyhow!("alerts.temp_critical must be a positive temperature in °C"));
+        }
+
+        if let Some(check) = self.health_checks.services
+            .iter()
+            .find(|check| check.weight.is_some_and(|weight| !weight.is_finite() || weight <= 0.0))
+        {
+            return Err(anyhow::anyhow!("Health check '{}': weight must be a positive number", check.name));
+        }
+
+        if self.ui.min_contrast.is_some_and(|ratio| !(1.0..=21.0).contains(&ratio)) {
+            return Err(anyhow::anyhow!("ui.min_contrast must be a contrast ratio between 1 and 21"));
+        }
+
+        if let Some(auto_theme) = &self.ui.auto_theme {
+            for name in [&auto_theme.light, &auto_theme.dark] {
+                if crate::theme::Theme::from_name(name).is_none() {
+                    return Err(anyhow::anyhow!("Unknown theme '{}' in ui.auto_theme", name));
+                }
+            }
+            for time in [&auto_theme.light_after, &auto_theme.dark_after] {
+                if crate::clock::parse_time_of_day(time).is_none() {
+                    return Err(anyhow::anyhow!("Invalid time '{}' in ui.auto_theme (expected HH:MM)", time));
+                }
+            }
+        }
+
+        if self.ui.tabs.is_empty() {
+            return Err(anyhow::anyhow!("ui.tabs must list at least one tab"));
+        }
+        for (index, tab) in self.ui.tabs.iter().enumerate() {
+            if self.ui.tabs[..index].contains(tab) {
+                return Err(anyhow::anyhow!("ui.tabs lists {} more than once", tab.label()));
+            }
+        }
 
         // Validate update intervals
         if self.general.update_interval_secs == 0 {
             return Err(anyhow::anyhow!("Update interval must be greater than 0"));
         }
+        if self.ui.refresh_rate_ms == 0 {
+            return Err(anyhow::anyhow!("UI refresh rate (ui.refresh_rate_ms) must be greater than 0"));
+        }
+        if self.health_checks.enabled && self.health_checks.interval_secs == 0 {
+            return Err(anyhow::anyhow!("health_checks.interval_secs must be greater than 0"));
+        }
+        let mut node_settings = std::iter::once(&self.nodes.defaults).chain(self.nodes.nodes.iter().filter_map(|node| node.overrides.as_ref()));
+        if let Some(settings) = node_settings.find(|settings| !["C", "F"].contains(&settings.temperature_unit.as_str())) {
+            return Err(anyhow::anyhow!("Invalid temperature_unit '{}' (use C or F)", settings.temperature_unit));
+        }
+        let mut node_settings = std::iter::once(&self.nodes.defaults).chain(self.nodes.nodes.iter().filter_map(|node| node.overrides.as_ref()));
+        if let Some(settings) = node_settings.find(|settings| !["KB/s", "MB/s", "GB/s"].contains(&settings.network_unit.as_str())) {
+            return Err(anyhow::anyhow!("Invalid network_unit '{}' (use KB/s, MB/s or GB/s)", settings.network_unit));
+        }
+        if self.logging.log_to_file && (self.logging.max_file_size_mb == 0 || self.logging.max_files == 0) {
+            return Err(anyhow::anyhow!("logging.max_file_size_mb and logging.max_files must be greater than 0"));
+        }
 
-        // Validate health check configurations
-        for service in &self.health_checks.services {
-            if service.enabled && service.endpoint.is_empty() {
-                return Err(anyhow::anyhow!("Service {} has health check enabled but empty endpoint", service.name));
+        // Validate health check endpoints, reporting every bad one at once
+        let mut invalid_endpoints = Vec::new();
+        let mut warnings = Vec::new();
+        for service in self.health_checks.services.iter().filter(|service| service.enabled) {
+            if service.endpoint.is_empty() {
+                invalid_endpoints.push(format!("{}: empty endpoint", service.name));
+                continue;
             }
+
+            match reqwest::Url::parse(&service.endpoint) {
+                Ok(url) if url.host_str().is_none_or(str::is_empty) => {
+                    invalid_endpoints.push(format!("{}: no host in {}", service.name, service.endpoint));
+                }
+                Ok(url) => {
+                    if !matches!(url.scheme(), "http" | "https") {
+                        warnings.push(format!(
+                            "health check {} uses {} with a {}:// endpoint; only http(s) endpoints can be checked",
+                            service.name, service.method, url.scheme()
+                        ));
+                    }
+                }
+                Err(e) => {
+                    invalid_endpoints.push(format!("{}: {} ({})", service.name, service.endpoint, e));
+                }
+            }
+        }
+        if !invalid_endpoints.is_empty() {
+            return Err(anyhow::anyhow!("Invalid health check endpoints:\n  {}", invalid_endpoints.join("\n  ")));
         }
 
-        Ok(())
+        // Validate alert rules
+        let invalid_rules: Vec<String> = self.alerts.rules
+            .iter()
+            .filter_map(|alert| {
+                crate::alerts::AlertRule::parse(alert)
+                    .err()
+                    .map(|e| format!("{}: {} ({:#})", alert.name, alert.rule, e))
+            })
+            .collect();
+        if !invalid_rules.is_empty() {
+            return Err(anyhow::anyhow!("Invalid alert rules:\n  {}", invalid_rules.join("\n  ")));
+        }
+
+        Ok(warnings)
+    }
+}
+
+/// A warning when a secrets file can be read by other users
+#[cfg(unix)]
+fn world_readable_warning(path: &std::path::Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = fs::metadata(path).ok()?;
+    (metadata.permissions().mode() & 0o004 != 0)
+        .then(|| format!("credentials file {} is world-readable; run `chmod 600` on it", path.display()))
+}
+
+#[cfg(not(unix))]
+fn world_readable_warning(_path: &std::path::Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `content` to a fresh credentials file under the temp dir
+    fn credentials_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("monitorium-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("credentials.yaml");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn alerts_may_be_a_section_or_a_list_of_rules() {
+        let parse = |yaml: &str| deserialize_alerts(serde_yaml::Deserializer::from_str(yaml)).unwrap();
+
+        let rules = parse("- name: High CPU\n  scope: node\n  rule: cpu_usage > 90\n");
+        assert_eq!(rules.rules.len(), 1);
+        assert_eq!((rules.temp_critical, rules.bell_mode), (None, BellMode::None));
+
+        let section = parse("rules:\n  - name: High CPU\n    scope: node\n    rule: cpu_usage > 90\ntemp_critical: 90\nbell_mode: visual\n");
+        assert_eq!(section.rules[0].rule, "cpu_usage > 90");
+        assert_eq!((section.temp_critical, section.bell_mode), (Some(90.0), BellMode::Visual));
+    }
+
+    #[test]
+    fn credentials_file_overrides_url_and_auth() {
+        let path = credentials_file("credentials", "url: https://prometheus.example.com\nusername: admin\npassword: secret\n");
+        let mut config = Config::default();
+        config.prometheus.credentials_file = Some(path.display().to_string());
+
+        config.merge_credentials_file().unwrap();
+        assert_eq!(config.prometheus.url, "https://prometheus.example.com");
+        let auth = config.prometheus.auth.as_ref().unwrap();
+        assert_eq!((auth.username.as_str(), auth.password.as_str()), ("admin", "secret"));
+        assert_eq!(auth.bearer_token, None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_readable_credentials_file_warns() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = credentials_file("credentials-mode", "bearer_token: t0ken\n");
+        let mut config = Config::default();
+        config.prometheus.credentials_file = Some(path.display().to_string());
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(config.merge_credentials_file().unwrap().is_some_and(|warning| warning.contains("world-readable")));
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        assert_eq!(config.merge_credentials_file().unwrap(), None);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn validate_rejects_zero_refresh_rate() {
+        let mut config = Config::default();
+        config.ui.refresh_rate_ms = 0;
+        assert!(config.validate().is_err());
+
+        config.ui.refresh_rate_ms = 1;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn auto_theme_periods_may_wrap_past_midnight() {
+        let mut auto_theme = AutoThemeConfig {
+            light: "solarized".to_string(),
+            dark: "dracula".to_string(),
+            light_after: default_light_after(),
+            dark_after: default_dark_after(),
+        };
+        let at = |time: &str| crate::clock::parse_time_of_day(time).unwrap();
+        assert!(!auto_theme.is_light_at(at("06:59")));
+        assert!(auto_theme.is_light_at(at("07:00")));
+        assert!(auto_theme.is_light_at(at("18:59")));
+        assert!(!auto_theme.is_light_at(at("19:00")));
+
+        // Night shift: light from 22:00 until 06:00
+        auto_theme.light_after = "22:00".to_string();
+        auto_theme.dark_after = "06:00".to_string();
+        assert!(auto_theme.is_light_at(at("23:30")));
+        assert!(auto_theme.is_light_at(at("05:59")));
+        assert!(!auto_theme.is_light_at(at("12:00")));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn clamp_limits_raises_history_retention_to_minimum() {
+        let mut config = Config::default();
+        let cases = [
+            (0, MIN_HISTORY_RETENTION),
+            (MIN_HISTORY_RETENTION - 1, MIN_HISTORY_RETENTION),
+            (MIN_HISTORY_RETENTION, MIN_HISTORY_RETENTION),
+            (60, 60),
+        ];
+        for (configured, expected) in cases {
+            config.general.history_retention = configured;
+            let warnings = config.clamp_limits();
+            assert_eq!(config.general.history_retention, expected);
+            assert_eq!(warnings.is_empty(), configured == expected);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+use anyhow::Context;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Quiet period after the last change before reloading; editors often
+/// write a file in several steps
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches the config file for `ui.watch_config`
+pub struct ConfigWatcher {
+    path: PathBuf,
+    // Dropping the watcher stops the notifications
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    changed_at: Option<Instant>,
+}
+
+impl ConfigWatcher {
+    /// Watch `path`. Its directory is watched rather than the file, since
+    /// editors that save by renaming replace the file we'd be watching.
+    pub fn new(path: PathBuf) -> anyhow::Result<Self> {
+        let dir = path.parent().context("Config file has no parent directory")?.to_path_buf();
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).context("Failed to create config file watcher")?;
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {}", dir.display()))?;
+        Ok(Self { path, _watcher: watcher, events, changed_at: None })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Whether the config file changed and has since been quiet for
+    /// `DEBOUNCE`; each burst of changes is reported once
+    pub fn poll(&mut self) -> bool {
+        let file_name = self.path.file_name();
+        for event in self.events.try_iter().flatten() {
+            let touches_config = event.paths.iter().any(|path| path.file_name() == file_name);
+            if touches_config && !event.kind.is_access() {
+                self.changed_at = Some(Instant::now());
+            }
+        }
+        if self.changed_at.is_some_and(|at| at.elapsed() >= DEBOUNCE) {
+            self.changed_at = None;
+            return true;
+        }
+        false
+    }
+}
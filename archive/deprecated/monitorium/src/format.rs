@@ -0,0 +1,163 @@
+use crate::config::{NumberLocale, UiConfig};
+
+/// Large counts with a k/M/G/T suffix (`1.2k`, `3.4M`); values under 1000
+/// keep one decimal
+pub fn compact_number(value: f64) -> String {
+    const SUFFIXES: [&str; 4] = ["k", "M", "G", "T"];
+
+    if value.abs() < 999.95 {
+        return format!("{:.1}", value);
+    }
+    let mut scaled = value;
+    let mut suffix = "";
+    for next in SUFFIXES {
+        // 999.95 and up would print as "1000.0", so move up a suffix
+        if scaled.abs() < 999.95 {
+            break;
+        }
+        scaled /= 1000.0;
+        suffix = next;
+    }
+    format!("{:.1}{}", scaled, suffix)
+}
+
+/// A count as shown in tables: compact unless `ui.compact_numbers` is off,
+/// with the separators of `ui.locale`
+pub fn count(value: f64, ui: &UiConfig) -> String {
+    let text = if ui.compact_numbers {
+        compact_number(value)
+    } else {
+        format!("{:.1}", value)
+    };
+    localize(&text, ui.locale)
+}
+
+/// Rewrite the numbers in `text`, formatted the default `{:.1}` way, with
+/// the decimal and thousands separators of `locale`
+pub fn localize(text: &str, locale: NumberLocale) -> String {
+    let (decimal, group) = match locale {
+        NumberLocale::En => return text.to_string(),
+        NumberLocale::De => (',', '.'),
+        NumberLocale::Fr => (',', ' '),
+        NumberLocale::Ch => ('.', '\''),
+    };
+    let chars: Vec<char> = text.chars().collect();
+    let mut localized = String::with_capacity(text.len() + 4);
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            localized.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        // Integer part, grouped in threes from the right
+        let start = i;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        for (n, &digit) in chars[start..i].iter().enumerate() {
+            if n > 0 && (i - start - n) % 3 == 0 {
+                localized.push(group);
+            }
+            localized.push(digit);
+        }
+        // Fraction, if a digit follows the point
+        if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(char::is_ascii_digit) {
+            localized.push(decimal);
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                localized.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+    localized
+}
+
+/// A Celsius reading in `unit` ("C" or "F") with its symbol
+pub fn convert_temperature(celsius: f64, unit: &str) -> (f64, &'static str) {
+    if unit.eq_ignore_ascii_case("F") {
+        (celsius * 9.0 / 5.0 + 32.0, "°F")
+    } else {
+        (celsius, "°C")
+    }
+}
+
+/// A rate in MB/s as a number in `unit` (KB/s, MB/s or GB/s) and that
+/// unit. KB/s has no decimals, GB/s two, MB/s one below 10 MB/s.
+pub fn scale_network_rate(mb_per_sec: f64, unit: &str) -> (String, &'static str) {
+    match unit {
+        "KB/s" => (format!("{:.0}", mb_per_sec * 1024.0), "KB/s"),
+        "GB/s" => (format!("{:.2}", mb_per_sec / 1024.0), "GB/s"),
+        _ if mb_per_sec < 10.0 => (format!("{:.1}", mb_per_sec), "MB/s"),
+        _ => (format!("{:.0}", mb_per_sec), "MB/s"),
+    }
+}
+
+/// A rate in MB/s converted to `unit`, e.g. `512 KB/s`
+pub fn format_network_rate(mb_per_sec: f64, unit: &str) -> String {
+    let (value, unit) = scale_network_rate(mb_per_sec, unit);
+    format!("{} {}", value, unit)
+}
+
+/// A duration in its largest whole unit (`3d`, `5h`, `12m`, `40s`)
+pub fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3_599 => format!("{}m", secs / 60),
+        3_600..=86_399 => format!("{}h", secs / 3_600),
+        _ => format!("{}d", secs / 86_400),
+    }
+}
+
+/// Uptime as days, hours and minutes (`7d 3h 12m`), leaving out leading zero
+/// units. 0 is what nodes and services without an uptime metric report, so
+/// it reads "unknown".
+pub fn format_uptime(secs: u64) -> String {
+    if secs == 0 {
+        return "unknown".to_string();
+    }
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h {}m", days, hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uptime_shows_days_hours_and_minutes() {
+        assert_eq!(format_uptime(0), "unknown");
+        assert_eq!(format_uptime(45), "0m");
+        assert_eq!(format_uptime(3_600 + 120), "1h 2m");
+        assert_eq!(format_uptime(7 * 86_400 + 3 * 3_600 + 12 * 60 + 59), "7d 3h 12m");
+    }
+
+    #[test]
+    fn temperatures_convert_to_fahrenheit() {
+        assert_eq!(convert_temperature(100.0, "F"), (212.0, "°F"));
+        assert_eq!(convert_temperature(-40.0, "F"), (-40.0, "°F"));
+        assert_eq!(convert_temperature(55.5, "C"), (55.5, "°C"));
+    }
+
+    #[test]
+    fn network_rates_convert_from_mb_per_sec() {
+        assert_eq!(format_network_rate(0.5, "KB/s"), "512 KB/s");
+        assert_eq!(format_network_rate(2.5, "MB/s"), "2.5 MB/s");
+        assert_eq!(format_network_rate(450.2, "MB/s"), "450 MB/s");
+        assert_eq!(format_network_rate(1536.0, "GB/s"), "1.50 GB/s");
+    }
+
+    #[test]
+    fn localize_swaps_separators_and_groups_thousands() {
+        assert_eq!(localize("12345.6/16GB", NumberLocale::En), "12345.6/16GB");
+        assert_eq!(localize("12345.6/16GB", NumberLocale::De), "12.345,6/16GB");
+        assert_eq!(localize("↓1200 ↑3MB/s", NumberLocale::Fr), "↓1 200 ↑3MB/s");
+        assert_eq!(localize("1234567.0%", NumberLocale::Ch), "1'234'567.0%");
+        assert_eq!(localize("1.2k.", NumberLocale::De), "1,2k.");
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use anyhow::Context;
+use reqwest::tls::TlsInfo;
+use reqwest::{Client, Method};
+use crate::config::ServiceHealthCheck;
+
+/// Outcome of one health check request
+#[derive(Debug, Clone)]
+pub struct HealthResult {
+    /// Service name of the check
+    pub name: String,
+    /// The endpoint answered with one of the expected status codes
+    pub passed: bool,
+    /// Passed, but slower than `response_time_threshold_ms`
+    pub slow: bool,
+    pub response_time_ms: f64,
+    /// Unix time the check was sent
+    pub checked_at: u64,
+    pub error: Option<String>,
+    /// Unix time the certificate served by an https:// endpoint expires,
+    /// read from this check's handshake (see `cert_expiry`)
+    pub cert_expires_at: Option<u64>,
+}
+
+/// Send the configured request and compare its status against
+/// `expected_status`. The client's timeout applies unless the check sets its
+/// own; build it with `tls_info(true)` to get certificate expiry dates.
+pub async fn run_health_check(client: &Client, check: &ServiceHealthCheck) -> HealthResult {
+    let checked_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let started = Instant::now();
+    let outcome = send(client, check).await;
+    let response_time_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let (error, cert_expires_at) = match outcome {
+        Ok((status, expires_at)) if check.expected_status.contains(&status) => (None, expires_at),
+        Ok((status, expires_at)) => (Some(format!("Unexpected status {} (expected {:?})", status, check.expected_status)), expires_at),
+        Err(e) => (Some(format!("{:#}", e)), None),
+    };
+    let slow = check.response_time_threshold_ms.is_some_and(|threshold| response_time_ms > threshold as f64);
+    HealthResult {
+        name: check.name.clone(),
+        passed: error.is_none(),
+        slow: error.is_none() && slow,
+        response_time_ms,
+        checked_at,
+        error,
+        cert_expires_at,
+    }
+}
+
+/// Status code of the check's response, and the expiry of the certificate
+/// it came with
+async fn send(client: &Client, check: &ServiceHealthCheck) -> anyhow::Result<(u16, Option<u64>)> {
+    let method = Method::from_bytes(check.method.to_uppercase().as_bytes())
+        .with_context(|| format!("Invalid HTTP method: {}", check.method))?;
+    let mut request = client.request(method, &check.endpoint);
+    if let Some(timeout_secs) = check.timeout_secs {
+        request = request.timeout(Duration::from_secs(timeout_secs));
+    }
+    for (name, value) in check.headers.iter().flatten() {
+        request = request.header(name, value);
+    }
+    if let Some(body) = &check.body {
+        request = request.body(body.clone());
+    }
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("Failed to reach {}", check.endpoint))?;
+    let cert_expires_at = response
+        .extensions()
+        .get::<TlsInfo>()
+        .and_then(TlsInfo::peer_certificate)
+        .and_then(crate::cert_expiry::not_after);
+    Ok((response.status().as_u16(), cert_expires_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn check(endpoint: String, expected_status: Vec<u16>) -> ServiceHealthCheck {
+        ServiceHealthCheck {
+            name: "n8n-0".to_string(),
+            endpoint,
+            method: "post".to_string(),
+            expected_status,
+            headers: Some([("X-Probe".to_string(), "1".to_string())].into()),
+            body: None,
+            enabled: true,
+            timeout_secs: None,
+            response_time_threshold_ms: None,
+            startup_grace_secs: 0,
+            critical: false,
+            weight: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn health_check_compares_status_and_latency() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/healthz"))
+            .and(header("X-Probe", "1"))
+            .respond_with(ResponseTemplate::new(204).set_delay(Duration::from_millis(50)))
+            .mount(&server)
+            .await;
+        let client = Client::new();
+        let endpoint = format!("{}/healthz", server.uri());
+
+        let result = run_health_check(&client, &check(endpoint.clone(), vec![200, 204])).await;
+        assert!(result.passed && !result.slow, "{:?}", result.error);
+        assert!(result.response_time_ms >= 50.0);
+        assert_eq!(result.cert_expires_at, None);
+
+        let mut slow = check(endpoint.clone(), vec![204]);
+        slow.response_time_threshold_ms = Some(10);
+        assert!(run_health_check(&client, &slow).await.slow);
+
+        let result = run_health_check(&client, &check(endpoint, vec![200])).await;
+        assert!(!result.passed);
+        assert_eq!(result.error.as_deref(), Some("Unexpected status 204 (expected [200])"));
+
+        let mut timed_out = check(format!("{}/healthz", server.uri()), vec![204]);
+        timed_out.timeout_secs = Some(0);
+        assert!(!run_health_check(&client, &timed_out).await.passed);
+    }
+}
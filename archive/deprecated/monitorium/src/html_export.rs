@@ -0,0 +1,131 @@
+use std::fmt::Write;
+use crate::app::{App, MetricAvailability};
+use crate::format::{convert_temperature, format_network_rate};
+
+/// Inline stylesheet so the file can be opened or mailed on its own
+const STYLE: &str = "
+body { font-family: ui-monospace, Menlo, Consolas, monospace; background: #1e1e2e; color: #cdd6f4; margin: 2em; }
+h1 { font-size: 1.4em; margin-bottom: 0; }
+p.meta { color: #9399b2; margin-top: 0.3em; }
+h2 { font-size: 1.1em; margin-top: 1.6em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.25em 0.9em; text-align: left; border-bottom: 1px solid #45475a; }
+th { color: #89dceb; }
+.good { color: #a6e3a1; }
+.warn { color: #f9e2af; }
+.crit { color: #f38ba8; }
+.muted { color: #9399b2; }
+";
+
+/// Self-contained HTML page with the node and service tables as shown now
+pub fn render(app: &App, created_at: u64) -> String {
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<p class=\"meta\">Snapshot taken {created_at} from {host} &middot; cluster CPU {cpu:.1}%</p>\n",
+        title = escape(&app.config.general.cluster_name),
+        created_at = format_utc(created_at),
+        host = escape(&app.config.prometheus.host()),
+        cpu = app.cluster_cpu_weighted(),
+    );
+
+    html.push_str("<h2>Nodes</h2>\n<table>\n<tr><th>Node</th><th>Status</th><th>CPU</th><th>Memory</th><th>GPU</th><th>Disk</th><th>Network</th><th>Temp</th></tr>\n");
+    for name in app.sorted_node_names() {
+        let node = &app.nodes[&name];
+        let gpu = if app.node_metric_availability(node, "gpu") == MetricAvailability::NotApplicable {
+            "<td class=\"muted\">—</td>".to_string()
+        } else {
+            usage_cell(app, node.gpu_usage)
+        };
+        let unit = app.node_network_unit(&name);
+        let (temperature, symbol) = convert_temperature(node.temperature, app.node_temperature_unit(&name));
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td>{}{}{}{}<td>&darr;{} &uarr;{}</td><td>{:.1}{}</td></tr>",
+            escape(&name),
+            escape(&node.status),
+            usage_cell(app, node.cpu_usage),
+            usage_cell(app, node.memory_usage),
+            gpu,
+            usage_cell(app, node.disk_usage),
+            format_network_rate(node.network_rx, unit),
+            format_network_rate(node.network_tx, unit),
+            temperature,
+            symbol,
+        );
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Services</h2>\n<table>\n<tr><th>Service</th><th>Namespace</th><th>Status</th><th>Health</th><th>CPU</th><th>Memory</th><th>RPS</th><th>Latency</th><th>Errors</th><th>Replicas</th></tr>\n");
+    for name in app.filtered_service_names() {
+        let service = &app.services[&name];
+        let health = app.health_status(service);
+        let health_class = match health {
+            "Healthy" => "good",
+            "Degraded" | "Starting" => "warn",
+            "Unhealthy" => "crit",
+            _ => "muted",
+        };
+        let status_class = if service.status == "Running" { "good" } else { "crit" };
+        let replicas_class = if service.ready_replicas == service.replicas { "good" } else { "warn" };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td class=\"{}\">{}</td>{}{}<td>{}</td><td>{:.0} ms</td><td>{:.1}%</td><td class=\"{}\">{}/{}</td></tr>",
+            escape(&name),
+            escape(&service.namespace),
+            status_class,
+            escape(&service.status),
+            health_class,
+            escape(health),
+            usage_cell(app, service.cpu_usage),
+            usage_cell(app, service.memory_usage),
+            crate::format::count(service.requests_per_sec, &app.config.ui),
+            service.response_time,
+            service.error_rate,
+            replicas_class,
+            service.ready_replicas,
+            service.replicas,
+        );
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+
+    html
+}
+
+/// Percentage cell colored by the configured usage thresholds
+fn usage_cell(app: &App, percent: f64) -> String {
+    let thresholds = &app.config.ui.thresholds;
+    let class = if percent > thresholds.critical {
+        "crit"
+    } else if percent > thresholds.warning {
+        "warn"
+    } else {
+        "good"
+    };
+    format!("<td class=\"{}\">{:.1}%</td>", class, percent)
+}
+
+/// Unix time as "YYYY-MM-DD HH:MM UTC"
+pub fn format_utc(secs: u64) -> String {
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let secs_of_day = secs % 86_400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, secs_of_day / 3600, secs_of_day % 3600 / 60)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,107 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use anyhow::{Context, Result};
+use tracing::Level;
+use crate::config::{Config, LoggingConfig};
+
+/// Send `tracing` events to `~/.monitorium/<log_file>` when `log_to_file` is
+/// set. Nothing goes to the terminal, which the TUI owns while it runs.
+pub fn init(config: &LoggingConfig) -> Result<()> {
+    if !config.log_to_file {
+        return Ok(());
+    }
+    let level = Level::from_str(&config.level).with_context(|| format!("Invalid log level: {}", config.level))?;
+    let path = Config::config_dir()?.join(config.log_file.as_deref().unwrap_or("monitorium.log"));
+    let file = RotatingFile::open(path, config.max_file_size_mb * 1024 * 1024, config.max_files)?;
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_ansi(false)
+        .with_writer(Mutex::new(file))
+        .try_init()
+        .map_err(|e| anyhow::anyhow!("Failed to start logging: {}", e))
+}
+
+/// Log file that moves to `<path>.1` once it would grow past `max_bytes`,
+/// shifting older files up and keeping `max_files` in total
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: u32,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64, max_files: u32) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open log file {}", path.display()))?;
+        let written = file.metadata().map_or(0, |metadata| metadata.len());
+        Ok(Self { path, file, written, max_bytes, max_files })
+    }
+
+    fn rotated(&self, index: u32) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        // The live file counts towards max_files
+        let keep = self.max_files.saturating_sub(1);
+        if keep > 0 {
+            for index in (1..keep).rev() {
+                let _ = fs::rename(self.rotated(index), self.rotated(index + 1));
+            }
+            fs::rename(&self.path, self.rotated(1))?;
+        }
+        self.file = File::create(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_files_rotate_by_size() {
+        let dir = std::env::temp_dir().join(format!("monitorium-logging-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut file = RotatingFile::open(dir.join("test.log"), 10, 3).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("test.log"), "fourth\n");
+        assert_eq!(read("test.log.1"), "third\n");
+        assert_eq!(read("test.log.2"), "second\n");
+        assert!(!dir.join("test.log.3").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,8 +1,9 @@
-use std::io::{self, stdout};
+use std::io::{self, stdout, Write};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
+use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -11,28 +12,106 @@ use ratatui::{
     Terminal,
 };
 
+mod alerts;
 mod app;
+mod baseline;
+mod clock;
+mod notes;
+mod scratchpad;
+mod view_state;
 mod mock_data;
 mod ui;
 mod theme;
 mod prometheus_client;
+mod power;
 mod config;
+mod config_watch;
+mod logging;
+mod cert_expiry;
+mod health_checker;
+mod format;
+mod html_export;
 
-use app::App;
+use app::{ActivePanel, App};
 use ui::ui;
 use config::Config;
 
+/// Terminal dashboard for homelab nodes and services
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Quit automatically after this long (e.g. "5s", "2m"), for CI and screenshots
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    exit_after: Option<Duration>,
+
+    /// Name of the baseline 'b' saves and 'B' compares against
+    #[arg(long, value_name = "NAME", default_value = "default", value_parser = parse_baseline_name)]
+    baseline: String,
+
+    /// Read the configuration from PATH instead of ~/.monitorium/config.yaml
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Print the effective configuration as YAML, secrets masked, and exit
+    #[arg(long, visible_alias = "print-config")]
+    dump_config: bool,
+}
+
+/// Baseline names become file names, so keep them to a safe character set
+fn parse_baseline_name(input: &str) -> Result<String, String> {
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) && !input.starts_with('.') {
+        Ok(input.to_string())
+    } else {
+        Err(format!("invalid baseline name '{}' (use letters, digits, '-', '_' and '.')", input))
+    }
+}
+
+/// Parse a duration like "500ms", "5s", "2m" or "1h" (bare numbers are seconds)
+pub(crate) fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit()).unwrap_or(input.len());
+    let (value, unit) = input.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", input))?;
+
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "" | "s" => Ok(Duration::from_secs(value)),
+        "m" => Ok(Duration::from_secs(value * 60)),
+        "h" => Ok(Duration::from_secs(value * 3600)),
+        _ => Err(format!("unknown duration unit '{}' (use ms, s, m or h)", unit)),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Load configuration
-    let config = Config::load()
-        .map_err(|e| format!("Failed to load configuration: {}", e))?;
+    let cli = Cli::parse();
+
+    // Load configuration; an explicit path must exist rather than getting a default
+    let (config, config_path) = match cli.config {
+        Some(path) => (Config::load_from(&path), path),
+        None => (Config::load(), Config::get_config_path()?),
+    };
+    let (config, mut warnings) = config.map_err(|e| format!("Failed to load configuration: {:#}", e))?;
+
+    // Before validation, so an invalid config can be inspected too
+    if cli.dump_config {
+        print!("{}", serde_yaml::to_string(&config.redacted())?);
+        return Ok(());
+    }
 
     // Validate configuration
-    config.validate()
-        .map_err(|e| format!("Configuration validation failed: {}", e))?;
+    warnings.extend(config.validate()
+        .map_err(|e| format!("Configuration validation failed: {}", e))?);
+    // Still before the TUI takes over the terminal
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    logging::init(&config.logging)
+        .map_err(|e| format!("Failed to set up logging: {:#}", e))?;
 
-    println!("Monitorium starting with configuration from: {}", Config::get_config_path().unwrap_or_else(|_| PathBuf::from("unknown")).display());
+    println!("Monitorium starting with configuration from: {}", config_path.display());
 
     // setup terminal
     enable_raw_mode()?;
@@ -42,8 +121,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     // create app and run it
-    let app = App::new_with_config(config).await?;
-    let res = run_app(&mut terminal, app).await;
+    let mut app = App::new_with_config(config, config_path).await?;
+    app.baseline_name = cli.baseline;
+    let res = run_app(&mut terminal, &mut app, cli.exit_after).await;
 
     // restore terminal
     disable_raw_mode()?;
@@ -57,49 +137,140 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let Err(err) = res {
         println!("{err:?}");
     }
+    if let Err(err) = app.save_view_state() {
+        eprintln!("{err:#}");
+    }
 
     Ok(())
 }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<()> {
+async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App, exit_after: Option<Duration>) -> io::Result<()> {
+    let started = Instant::now();
     let mut last_tick = Instant::now();
     let mut last_prometheus_update = Instant::now();
-    let tick_rate = Duration::from_millis(app.config.ui.refresh_rate_ms);
-    let prometheus_update_rate = Duration::from_secs(app.config.prometheus.query_interval_secs);
 
     loop {
-        terminal.draw(|f| ui(f, &app))?;
+        if !app.paused {
+            app.poll_prometheus_update();
+            app.poll_health_check();
+        }
+        app.scratchpad.poll();
+        app.poll_graph_backfill();
+        terminal.draw(|f| ui(f, app))?;
 
+        // Same as pressing 'q' once the requested run time is up
+        if exit_after.is_some_and(|limit| started.elapsed() >= limit) {
+            return Ok(());
+        }
+
+        // Re-read each frame: the battery saver can change it at runtime
+        let tick_rate = app.tick_rate();
         let timeout = tick_rate
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
 
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
+            match event::read()? {
+                // Keys go to the unified list's search box while it's open
+                Event::Key(key) if app.unified_typing => app.unified_search_key(key.code),
+                Event::Key(key) if app.note_editing.is_some() => app.note_key(key.code),
+                Event::Key(key) if app.service_filter_input.is_some() => app.service_filter_key(key.code),
+                Event::Key(key) if app.scratchpad.open => app.scratchpad_key(key.code),
+                Event::Key(key) if app.show_help => match key.code {
                     KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Tab => app.switch_panel(),
-                    KeyCode::Up => app.navigate_up(),
-                    KeyCode::Down => app.navigate_down(),
+                    KeyCode::Char('?') | KeyCode::F(1) | KeyCode::Esc => app.toggle_help(),
+                    _ => {}
+                },
+                Event::Key(key) => match key.code {
+                    KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        match app.dump_state() {
+                            Ok(path) => app.set_status_message(format!("State written to {}", path.display())),
+                            Err(e) => app.set_status_message(format!("State dump failed: {:#}", e)),
+                        }
+                    }
+                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        // Redraw to get at the frame's buffer
+                        let text = ui::buffer_text(terminal.draw(|f| ui(f, app))?.buffer);
+                        match app.export_frame_text(&text) {
+                            Ok(path) => app.set_status_message(format!("Frame text written to {}", path.display())),
+                            Err(e) => app.set_status_message(format!("Frame dump failed: {:#}", e)),
+                        }
+                    }
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => app.reset_view_state(),
+                    KeyCode::Home if key.modifiers.contains(KeyModifiers::CONTROL) => app.reset_view_state(),
+                    KeyCode::Char('H') => match app.export_html() {
+                        Ok(path) => app.set_status_message(format!("HTML snapshot written to {}", path.display())),
+                        Err(e) => app.set_status_message(format!("HTML export failed: {:#}", e)),
+                    },
+                    KeyCode::Char('b') => match app.save_baseline() {
+                        Ok(path) => app.set_status_message(format!("Baseline saved to {}", path.display())),
+                        Err(e) => app.set_status_message(format!("Saving baseline failed: {:#}", e)),
+                    },
+                    KeyCode::Char('B') => app.toggle_baseline(),
+                    KeyCode::Char('P') => app.toggle_pin(),
+                    KeyCode::Char('p') => app.toggle_pause(),
+                    KeyCode::Char('n') => app.start_note_edit(),
+                    KeyCode::Char('D') => app.toggle_diagnostics(),
+                    KeyCode::Enter => app.toggle_node_detail(),
+                    KeyCode::Esc => app.show_node_detail = false,
+                    KeyCode::Char(':') => app.scratchpad.open(),
+                    KeyCode::Char(zoom @ ('+' | '-')) if app.zoom_graphs(zoom == '+') => app.refill_graph_history(),
+                    KeyCode::Char('u') => app.toggle_unified_view(),
+                    KeyCode::Char('/') if app.unified_view => app.start_unified_search(),
+                    KeyCode::Char('/') => app.start_service_filter(),
+                    KeyCode::Char('q') => return Ok(()),
+                    KeyCode::Tab => app.next_tab(),
+                    KeyCode::BackTab => app.previous_tab(),
+                    KeyCode::Char(digit @ '1'..='9') => app.select_tab(digit as usize - '1' as usize),
+                    KeyCode::Char('w') => app.switch_panel(),
+                    KeyCode::Up | KeyCode::Char('k') => app.navigate_up(),
+                    KeyCode::Down | KeyCode::Char('j') => app.navigate_down(),
+                    KeyCode::Char('h' | 'l') => app.switch_panel(),
                     KeyCode::Left => app.previous_service(),
                     KeyCode::Right => app.next_node(),
                     KeyCode::Char('r') => app.toggle_filter(),
+                    KeyCode::Char('R') => app.force_refresh(),
                     KeyCode::Char(' ') => app.toggle_selection(),
                     KeyCode::Char('t') => app.next_theme(),
                     KeyCode::Char('T') => app.previous_theme(),
-                    KeyCode::Char('h') | KeyCode::F(1) => return Ok(()), // Help/quit alternative
+                    KeyCode::Char('%') => app.toggle_display_mode(),
+                    KeyCode::Char('L') => app.toggle_legend(),
+                    KeyCode::Char('s') if app.active_panel == ActivePanel::Services => app.cycle_service_sort(),
+                    KeyCode::Char('S') if app.active_panel == ActivePanel::Services => app.toggle_service_sort_direction(),
+                    KeyCode::Char('s') => app.cycle_node_sort(),
+                    KeyCode::Char('S') => app.toggle_node_sort_direction(),
+                    KeyCode::Char('v') => app.toggle_show_queries(),
+                    KeyCode::Char('?') | KeyCode::F(1) => app.toggle_help(),
+                    _ => {}
+                },
+                Event::Mouse(mouse) => match mouse.kind {
+                    MouseEventKind::Moved => app.on_mouse_move(mouse.column, mouse.row),
+                    MouseEventKind::Down(MouseButton::Left) => app.on_mouse_click(mouse.column, mouse.row),
                     _ => {}
-                }
+                },
+                _ => {}
             }
         }
 
-        if last_tick.elapsed() >= tick_rate {
+        if app.paused {
+            last_tick = Instant::now();
+        } else if last_tick.elapsed() >= tick_rate {
             app.on_tick();
             last_tick = Instant::now();
 
-            // Update Prometheus metrics less frequently
+            if app.take_bell() {
+                let mut out = stdout();
+                out.write_all(b"\x07")?;
+                out.flush()?;
+            }
+
+            app.start_health_check();
+
+            // Update Prometheus metrics less frequently, in the background. Re-read
+            // each tick: a config reload can change the interval.
+            let prometheus_update_rate = Duration::from_secs(app.config.prometheus.query_interval_secs);
             if last_prometheus_update.elapsed() >= prometheus_update_rate {
-                app.update_prometheus_metrics().await;
+                app.start_prometheus_update();
                 last_prometheus_update = Instant::now();
             }
         }
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct NodeMetrics {
     pub name: String,
     pub ip_address: String,
@@ -8,14 +9,22 @@ pub struct NodeMetrics {
     pub cpu_usage: f64,
     pub memory_usage: f64,
     pub memory_total: u64,
+    // Percentage of swap in use (0 when the node has no swap)
+    pub swap_usage: f64,
     pub gpu_usage: f64,
     pub gpu_memory: f64,
     pub gpu_memory_total: u64,
     pub network_rx: f64,
     pub network_tx: f64,
+    // Receive errors and drops per second, summed over interfaces
+    pub network_errors: f64,
+    pub network_drops: f64,
     pub disk_usage: f64,
     pub uptime: u64,
     pub temperature: f64,
+    pub load_avg: [f64; 3], // 1, 5 and 15 minute load averages
+    // Process groups by CPU, busiest first (see `prometheus.process_query`)
+    pub top_processes: Vec<ProcessUsage>,
     // Hardware specification fields
     pub cpu_model: String,
     pub cpu_cores: u32,
@@ -23,9 +32,14 @@ pub struct NodeMetrics {
     pub memory_total_gb: f64,
     pub gpu_model: String,
     pub disk_total_gb: f64,
+    // Metrics that returned no data on the last poll (see `MissingDataPolicy::MarkStale`)
+    pub stale_metrics: HashSet<&'static str>,
+    // Metrics Prometheus has never had a series for on this node (no sensor,
+    // exporter not installed), as opposed to ones that stopped reporting
+    pub unavailable_metrics: HashSet<&'static str>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ServiceMetrics {
     pub name: String,
     pub namespace: String,
@@ -44,6 +58,50 @@ pub struct ServiceMetrics {
     pub last_health_check: u64, // Unix timestamp
     pub health_response_time: f64, // Health check response time in ms
     pub consecutive_failures: u32,
+    // Container image from kube_pod_container_info ("unknown" when not reported)
+    pub image: String,
+    // Seconds since the pod was created, from kube_pod_created (0 when unknown)
+    pub age: u64,
+    // Recent Kubernetes events for the pod (see `prometheus.events_query`)
+    pub events: Vec<ServiceEvent>,
+    // Unix time the health check endpoint's TLS certificate expires
+    // (see `health_checks.cert_warning_days`)
+    pub cert_expires_at: Option<u64>,
+    // Metrics that returned no data on the last poll (see `MissingDataPolicy::MarkStale`)
+    pub stale_metrics: HashSet<&'static str>,
+}
+
+/// A Kubernetes event reported for a service's pod
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServiceEvent {
+    /// "Warning" or "Normal"
+    pub kind: String,
+    pub reason: String,
+    /// Empty when the exporter doesn't report messages
+    pub message: String,
+    /// Occurrences within the query's window
+    pub count: u64,
+}
+
+impl ServiceEvent {
+    pub fn is_warning(&self) -> bool {
+        self.kind.eq_ignore_ascii_case("warning")
+    }
+}
+
+/// CPU used by one process group on a node
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessUsage {
+    pub name: String,
+    /// Percent of one core, so busy multi-threaded groups exceed 100
+    pub cpu_usage: f64,
+}
+
+impl NodeMetrics {
+    /// Whether the node reports a GPU at all (the GPU column shows — otherwise)
+    pub fn has_gpu(&self) -> bool {
+        self.gpu_memory_total > 0 || self.gpu_usage > 0.0
+    }
 }
 
 pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String, ServiceMetrics>) {
@@ -66,6 +124,9 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         disk_usage: 52.3,
         uptime: 86400 * 7, // 7 days
         temperature: 65.2,
+        load_avg: [1.2, 0.9, 0.8],
+        unavailable_metrics: HashSet::new(),
+        top_processes: Vec::new(),
         // Hardware specs
         cpu_model: "Intel Core i5-12400F".to_string(),
         cpu_cores: 6,
@@ -73,6 +134,10 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         memory_total_gb: 32.0,
         gpu_model: "AMD Radeon RX 7800 XT".to_string(),
         disk_total_gb: 937.0,
+        network_errors: 0.0,
+        network_drops: 0.0,
+        swap_usage: 0.0,
+        stale_metrics: HashSet::new(),
     });
 
     nodes.insert("asuna".to_string(), NodeMetrics {
@@ -90,6 +155,9 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         disk_usage: 78.5,
         uptime: 86400 * 30, // 30 days
         temperature: 42.1,
+        load_avg: [2.4, 2.1, 1.9],
+        unavailable_metrics: HashSet::new(),
+        top_processes: Vec::new(),
         // Hardware specs
         cpu_model: "Intel Core i7-4510U".to_string(),
         cpu_cores: 2,
@@ -97,6 +165,10 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         memory_total_gb: 8.0,
         gpu_model: "Integrated Intel HD Graphics".to_string(),
         disk_total_gb: 98.0,
+        network_errors: 0.0,
+        network_drops: 0.0,
+        swap_usage: 4.2,
+        stale_metrics: HashSet::new(),
     });
 
     // Services - matching your K3s setup
@@ -118,6 +190,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318400, // Recent timestamp
         health_response_time: 45.2,
         consecutive_failures: 0,
+        image: "n8nio/n8n:1.19.4".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 14,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     services.insert("postgres-0".to_string(), ServiceMetrics {
@@ -138,6 +215,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318420, // Recent timestamp
         health_response_time: 12.8,
         consecutive_failures: 0,
+        image: "postgres:16.1".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 30,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     services.insert("redis-0".to_string(), ServiceMetrics {
@@ -158,6 +240,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318435, // Recent timestamp
         health_response_time: 8.4,
         consecutive_failures: 0,
+        image: "redis:7.2.3".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 21,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     services.insert("prometheus-0".to_string(), ServiceMetrics {
@@ -178,6 +265,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318450,
         health_response_time: 15.3,
         consecutive_failures: 0,
+        image: "prom/prometheus:v2.48.0".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 25,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     services.insert("grafana-0".to_string(), ServiceMetrics {
@@ -198,6 +290,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318465,
         health_response_time: 22.1,
         consecutive_failures: 0,
+        image: "grafana/grafana:10.2.2".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 18,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     services.insert("qdrant-0".to_string(), ServiceMetrics {
@@ -218,6 +315,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318480,
         health_response_time: 125.6,
         consecutive_failures: 2,
+        image: "qdrant/qdrant:v1.7.0".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 12,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     services.insert("flowise-0".to_string(), ServiceMetrics {
@@ -238,6 +340,11 @@ pub fn generate_mock_metrics() -> (HashMap<String, NodeMetrics>, HashMap<String,
         last_health_check: 1733318490,
         health_response_time: 0.0, // No response
         consecutive_failures: 5,
+        image: "flowiseai/flowise:1.4.3".to_string(),
+        cert_expires_at: None,
+        age: 86400 * 10,
+        events: Vec::new(),
+        stale_metrics: HashSet::new(),
     });
 
     (nodes, services)
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use anyhow::Context;
+use crate::config::Config;
+
+/// Free-text notes attached to services, kept across runs
+#[derive(Debug, Clone, Default)]
+pub struct Notes {
+    entries: HashMap<String, String>,
+}
+
+impl Notes {
+    /// `~/.monitorium/notes.json`
+    pub fn path() -> anyhow::Result<PathBuf> {
+        Ok(Config::config_dir()?.join("notes.json"))
+    }
+
+    /// The saved notes, or none if nothing was saved yet
+    pub fn load() -> anyhow::Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read notes: {}", path.display()))?;
+        let entries = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse notes: {}", path.display()))?;
+        Ok(Self { entries })
+    }
+
+    pub fn save(&self) -> anyhow::Result<PathBuf> {
+        let path = Self::path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create config directory: {}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(&self.entries).context("Failed to serialize notes")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write notes: {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn get(&self, service: &str) -> Option<&str> {
+        self.entries.get(service).map(String::as_str)
+    }
+
+    /// Set a service's note; an empty note removes it
+    pub fn set(&mut self, service: &str, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.entries.remove(service);
+        } else {
+            self.entries.insert(service.to_string(), note.to_string());
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::fs;
+use std::path::Path;
+
+/// Whether the machine is running on battery, i.e. some battery under
+/// `/sys/class/power_supply` is discharging. Always false where that
+/// interface doesn't exist.
+pub fn on_battery() -> bool {
+    let Ok(supplies) = fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+
+    supplies
+        .flatten()
+        .any(|supply| read_attribute(&supply.path(), "type") == "Battery"
+            && read_attribute(&supply.path(), "status") == "Discharging")
+}
+
+fn read_attribute(supply: &Path, name: &str) -> String {
+    fs::read_to_string(supply.join(name))
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default()
+}
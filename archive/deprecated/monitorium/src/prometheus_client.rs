@@ -1,81 +1,163 @@
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{Duration, Instant};
-use crate::mock_data::{NodeMetrics, ServiceMetrics};
+use crate::config::{Aggregation, MissingDataPolicy, PrometheusConfig};
+use crate::mock_data::{NodeMetrics, ProcessUsage, ServiceEvent, ServiceMetrics};
 
-#[derive(Debug, Clone, Deserialize)]
-pub struct PrometheusConfig {
-    pub url: String,
-    pub timeout_secs: u64,
-    pub query_interval_secs: u64,
-}
-
-impl Default for PrometheusConfig {
-    fn default() -> Self {
-        Self {
-            url: "http://100.81.76.55:30090".to_string(),
-            timeout_secs: 10,
-            query_interval_secs: 5,
-        }
-    }
-}
+/// Writes one metric into a service
+type ServiceSetter = fn(&mut ServiceMetrics, f64);
 
 #[derive(Debug, Deserialize)]
 struct PrometheusResponse {
     pub status: String,
+    // Absent when status is "error"
+    #[serde(default)]
     pub data: PrometheusData,
+    #[serde(rename = "errorType")]
+    pub error_type: Option<String>,
+    pub error: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct PrometheusData {
-    pub resultType: String,
+    // Only read by tests: every query here expects an instant or range vector
+    #[allow(dead_code)]
+    #[serde(rename = "resultType")]
+    pub result_type: String,
     pub result: Vec<PrometheusMetric>,
 }
 
 #[derive(Debug, Deserialize)]
 struct PrometheusMetric {
     pub metric: HashMap<String, String>,
+    // `[timestamp, value]` for instant queries
+    #[serde(default)]
     pub value: Vec<serde_json::Value>,
+    // `[[timestamp, value], ...]` for range queries
+    #[serde(default)]
+    pub values: Vec<Vec<serde_json::Value>>,
 }
 
 impl PrometheusMetric {
-    pub fn value(&self) -> f64 {
-        // Prometheus returns [timestamp, value], so we want the second element (index 1)
-        if self.value.len() >= 2 {
-            match &self.value[1] {
-                serde_json::Value::Number(n) => n.as_f64().unwrap_or(0.0),
-                serde_json::Value::String(s) => s.parse().unwrap_or(0.0),
-                _ => 0.0,
-            }
-        } else {
-            0.0
-        }
+    /// The sample value, or None if the `[timestamp, value]` pair is malformed
+    /// or the value isn't a finite number (Prometheus sends "NaN" for 0/0)
+    pub fn value(&self) -> Option<f64> {
+        let value = match self.value.as_slice() {
+            [_, serde_json::Value::Number(n)] => n.as_f64()?,
+            [_, serde_json::Value::String(s)] => s.parse().ok()?,
+            _ => return None,
+        };
+        value.is_finite().then_some(value)
+    }
+
+    /// The finite values of a range query's series, oldest first
+    pub fn range_values(&self) -> Vec<f64> {
+        self.values
+            .iter()
+            .filter_map(|pair| match pair.as_slice() {
+                [_, serde_json::Value::String(s)] => s.parse::<f64>().ok(),
+                [_, serde_json::Value::Number(n)] => n.as_f64(),
+                _ => None,
+            })
+            .filter(|value| value.is_finite())
+            .collect()
     }
 }
 
+/// Redirects followed per request before giving up
+const MAX_REDIRECTS: usize = 5;
+
+/// Scrape job of each service, as (job, service). The job narrows the
+/// `service_status` query and identifies the service's container series.
+const SERVICE_JOBS: [(&str, &str); 7] = [
+    ("n8n", "n8n-0"),
+    ("postgres", "postgres-0"),
+    ("redis", "redis-0"),
+    ("prometheus", "prometheus-0"),
+    ("grafana", "grafana-0"),
+    ("qdrant", "qdrant-0"),
+    ("flowise", "flowise-0"),
+];
+
+/// How one query has fared since launch
+#[derive(Debug, Clone, Default)]
+pub struct QueryStats {
+    pub successes: u64,
+    pub failures: u64,
+    pub last_ok: bool,
+    pub last_latency: Duration,
+    pub last_error: Option<String>,
+}
+
 pub struct PrometheusClient {
     client: Client,
     config: PrometheusConfig,
+    // Configured nodes as (name, address), matched against `instance` labels
+    node_targets: Vec<(String, String)>,
+    // Matchers added to every metric selector of every query
+    label_selectors: Vec<(String, String)>,
+    // Log each query with its latency and outcome
+    log_queries: bool,
     last_update: Option<Instant>,
     cached_nodes: HashMap<String, NodeMetrics>,
     cached_services: HashMap<String, ServiceMetrics>,
+    // Queries answered during the current update, to tell a total failure apart
+    successful_queries: AtomicUsize,
+    // Per-query outcomes, keyed by the configured expression
+    query_stats: Mutex<HashMap<String, QueryStats>>,
+    // (node, metric) pairs that have had a series at least once
+    reported_metrics: Mutex<HashSet<(String, &'static str)>>,
 }
 
 impl PrometheusClient {
-    pub fn new(config: PrometheusConfig) -> Result<Self> {
+    pub fn new(
+        mut config: PrometheusConfig,
+        node_targets: Vec<(String, String)>,
+        label_selectors: Vec<(String, String)>,
+        log_queries: bool,
+    ) -> Result<Self> {
+        // "http://host:9090/" would otherwise request "//api/v1/query",
+        // which some reverse proxies reject
+        config.url = normalize_base_url(&config.url);
+        config.service_prometheus_url = config.service_prometheus_url.as_deref().map(normalize_base_url);
+
+        let user_agent = config.user_agent
+            .clone()
+            .unwrap_or_else(|| format!("monitorium/{}", env!("CARGO_PKG_VERSION")));
         let client = Client::builder()
             .timeout(Duration::from_secs(config.timeout_secs))
+            // Lets operators attribute query load in Prometheus access logs
+            .user_agent(user_agent)
+            // Sends Accept-Encoding and transparently decodes the body
+            .gzip(config.accept_compression)
+            .deflate(config.accept_compression)
+            // Reuse connections across polls instead of reconnecting every query
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+            // Follow e.g. http -> https or /prometheus -> /prometheus/ redirects
+            // from proxies, but give up on loops quickly
+            .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
             .build()
             .context("Failed to create HTTP client")?;
 
         Ok(Self {
             client,
             config,
+            node_targets,
+            label_selectors,
+            log_queries,
             last_update: None,
             cached_nodes: HashMap::new(),
             cached_services: HashMap::new(),
+            successful_queries: AtomicUsize::new(0),
+            query_stats: Mutex::new(HashMap::new()),
+            reported_metrics: Mutex::new(HashSet::new()),
         })
     }
 
@@ -89,13 +171,15 @@ impl PrometheusClient {
             }
         }
 
+        self.successful_queries.store(0, Ordering::Relaxed);
+
         // Update node metrics
         match self.fetch_node_metrics().await {
             Ok(nodes) => {
                 self.cached_nodes = nodes;
             }
             Err(e) => {
-                eprintln!("Warning: Failed to fetch node metrics: {}", e);
+                tracing::warn!("Failed to fetch node metrics: {:#}", e);
                 // Don't return error immediately, try services too
             }
         }
@@ -106,14 +190,22 @@ impl PrometheusClient {
                 self.cached_services = services;
             }
             Err(e) => {
-                eprintln!("Warning: Failed to fetch service metrics: {}", e);
+                tracing::warn!("Failed to fetch service metrics: {:#}", e);
             }
         }
 
         self.last_update = Some(now);
+        if self.successful_queries.load(Ordering::Relaxed) == 0 {
+            return Err(anyhow::anyhow!("Every Prometheus query failed"));
+        }
         Ok(true) // Updated successfully
     }
 
+    /// Let the next `update_metrics` query even if the interval hasn't passed
+    pub fn force_next_update(&mut self) {
+        self.last_update = None;
+    }
+
     pub fn get_nodes(&self) -> &HashMap<String, NodeMetrics> {
         &self.cached_nodes
     }
@@ -123,9 +215,79 @@ impl PrometheusClient {
     }
 
     async fn fetch_node_metrics(&self) -> Result<HashMap<String, NodeMetrics>> {
+        // Start from the last known values so missing series can be retained
+        let mut nodes = if self.cached_nodes.is_empty() {
+            Self::fallback_nodes()
+        } else {
+            self.cached_nodes.clone()
+        };
+
+        // Try to get real metrics from Prometheus
+        let queries = &self.config.node_queries;
+        if let Ok(cpu_result) = self.query_prometheus(&queries.cpu_usage).await {
+            self.update_node_cpu(&mut nodes, &cpu_result);
+        }
+
+        if let Ok(mem_result) = self.query_prometheus(&queries.memory_usage).await {
+            self.update_node_value(&mut nodes, &mem_result, "memory_usage", "memory", |node, value| node.memory_usage = value);
+        }
+
+        for (i, (name, query)) in [("load1", &queries.load1), ("load5", &queries.load5), ("load15", &queries.load15)].into_iter().enumerate() {
+            if let Ok(load_result) = self.query_prometheus(query).await {
+                self.update_node_value(&mut nodes, &load_result, name, "load", |node, value| node.load_avg[i] = value);
+            }
+        }
+
+        if let Ok(errors_result) = self.query_prometheus(&queries.network_errors).await {
+            self.update_node_value(&mut nodes, &errors_result, "network_errors", "network_errors", |node, value| node.network_errors = value);
+        }
+
+        if let Ok(drops_result) = self.query_prometheus(&queries.network_drops).await {
+            self.update_node_value(&mut nodes, &drops_result, "network_drops", "network_drops", |node, value| node.network_drops = value);
+        }
+
+        if let Ok(swap_result) = self.query_prometheus(&queries.swap_usage).await {
+            self.update_node_value(&mut nodes, &swap_result, "swap_usage", "swap", |node, value| node.swap_usage = value);
+        }
+
+        if let Ok(disk_result) = self.query_prometheus(&queries.disk_usage).await {
+            self.update_node_disk(&mut nodes, &disk_result);
+        }
+
+        if let Ok(uptime_result) = self.query_prometheus(&queries.uptime).await {
+            self.update_node_value(&mut nodes, &uptime_result, "uptime", "uptime", |node, value| node.uptime = value.max(0.0) as u64);
+        }
+
+        let rx_result = self.query_prometheus(&queries.network_rx).await.ok();
+        let tx_result = self.query_prometheus(&queries.network_tx).await.ok();
+        self.update_node_network(&mut nodes, rx_result.as_ref(), tx_result.as_ref());
+
+        // Optional queries: nodes without a GPU or sensors may not export these at all
+        if let Some(gpu_query) = &queries.gpu_usage {
+            if let Ok(gpu_result) = self.query_prometheus(gpu_query).await {
+                self.update_node_gpu(&mut nodes, &gpu_result);
+            }
+        }
+
+        if let Some(temperature_query) = &queries.temperature {
+            if let Ok(temperature_result) = self.query_prometheus(temperature_query).await {
+                self.update_node_temperature(&mut nodes, &temperature_result);
+            }
+        }
+
+        if let Some(process_query) = &self.config.process_query {
+            if let Ok(process_result) = self.query_prometheus(process_query).await {
+                self.update_node_processes(&mut nodes, &process_result);
+            }
+        }
+
+        Ok(nodes)
+    }
+
+    fn fallback_nodes() -> HashMap<String, NodeMetrics> {
         let mut nodes = HashMap::new();
 
-        // Start with fallback mock data with real hardware specs
+        // Fallback mock data with real hardware specs
         nodes.insert("pesubuntu".to_string(), NodeMetrics {
             name: "pesubuntu".to_string(),
             ip_address: "192.168.8.106".to_string(),
@@ -141,6 +303,9 @@ impl PrometheusClient {
             disk_usage: 52.0,
             uptime: 0,
             temperature: 65.0,
+            load_avg: [0.0, 0.0, 0.0],
+            unavailable_metrics: HashSet::new(),
+            top_processes: Vec::new(),
             // Hardware specifications
             cpu_model: "Intel Core i5-12400F".to_string(),
             cpu_cores: 6,
@@ -148,6 +313,10 @@ impl PrometheusClient {
             memory_total_gb: 32.0,
             gpu_model: "AMD Radeon RX 7800 XT".to_string(),
             disk_total_gb: 937.0,
+            network_errors: 0.0,
+            network_drops: 0.0,
+            swap_usage: 0.0,
+            stale_metrics: HashSet::new(),
         });
 
         nodes.insert("asuna".to_string(), NodeMetrics {
@@ -165,6 +334,9 @@ impl PrometheusClient {
             disk_usage: 78.0,
             uptime: 0,
             temperature: 42.0,
+            load_avg: [0.0, 0.0, 0.0],
+            unavailable_metrics: HashSet::new(),
+            top_processes: Vec::new(),
             // Hardware specifications (service node specs)
             cpu_model: "Intel Core i7-4510U".to_string(),
             cpu_cores: 2,
@@ -172,24 +344,95 @@ impl PrometheusClient {
             memory_total_gb: 8.0,
             gpu_model: "Integrated Intel HD Graphics".to_string(),
             disk_total_gb: 98.0,
+            network_errors: 0.0,
+            network_drops: 0.0,
+            swap_usage: 0.0,
+            stale_metrics: HashSet::new(),
         });
 
-        // Try to get real metrics from Prometheus
-        if let Ok(cpu_result) = self.query_prometheus("100 - (avg by (instance) (irate(node_cpu_seconds_total{mode=\"idle\"}[5m])) * 100)").await {
-            self.update_node_cpu(&mut nodes, &cpu_result);
+        nodes
+    }
+
+    async fn fetch_service_metrics(&self) -> Result<HashMap<String, ServiceMetrics>> {
+        // Start from the last known values so missing series can be retained
+        let mut services = if self.cached_services.is_empty() {
+            Self::fallback_services()
+        } else {
+            self.cached_services.clone()
+        };
+
+        // Try to get real service status from Prometheus, narrowing the
+        // status query to each service's scrape job
+        let queries = &self.config.service_queries;
+        for (job, service_name) in SERVICE_JOBS {
+            let query = with_label_selectors(&queries.service_status, &[("job".to_string(), job.to_string())]);
+            if let Ok(up_result) = self.query_service_prometheus(&query).await {
+                self.update_service_status(&mut services, &up_result, service_name);
+            }
         }
 
-        if let Ok(mem_result) = self.query_prometheus("((1 - (node_memory_MemAvailable_bytes / node_memory_MemTotal_bytes)) * 100)").await {
-            self.update_node_memory(&mut nodes, &mem_result);
+        if let Ok(cpu_result) = self.query_service_prometheus(&queries.cpu_usage).await {
+            self.update_service_cpu(&mut services, &cpu_result);
         }
 
-        Ok(nodes)
+        if let Ok(memory_result) = self.query_service_prometheus(&queries.memory_usage).await {
+            self.update_service_memory(&mut services, &memory_result);
+        }
+
+        if let Ok(rps_result) = self.query_service_prometheus(&queries.requests_per_sec).await {
+            self.update_service_rps(&mut services, &rps_result);
+        }
+
+        if let Ok(latency_result) = self.query_service_prometheus(&queries.response_time).await {
+            self.update_service_latency(&mut services, &latency_result);
+        }
+
+        if let Ok(errors_result) = self.query_service_prometheus(&queries.error_rate).await {
+            self.update_service_error_rate(&mut services, &errors_result);
+        }
+
+        if let Ok(info_result) = self.query_service_prometheus(&queries.container_info).await {
+            self.update_service_images(&mut services, &info_result);
+        }
+
+        if let Ok(created_result) = self.query_service_prometheus(&queries.pod_created).await {
+            self.update_service_ages(&mut services, &created_result);
+        }
+
+        if let Some(events_query) = &self.config.events_query {
+            if let Ok(events_result) = self.query_service_prometheus(events_query).await {
+                self.update_service_events(&mut services, &events_result);
+            }
+        }
+
+        for (service_name, overrides) in &self.config.service_overrides {
+            let Some(service) = services.get_mut(service_name) else {
+                continue;
+            };
+            let queries: [(&Option<String>, &'static str, ServiceSetter); 5] = [
+                (&overrides.cpu_usage, "cpu", |service, value| service.cpu_usage = value),
+                (&overrides.memory_usage, "memory", |service, value| service.memory_usage = value),
+                (&overrides.requests_per_sec, "rps", |service, value| service.requests_per_sec = value),
+                (&overrides.response_time, "latency", |service, value| service.response_time = value),
+                (&overrides.error_rate, "errors", |service, value| service.error_rate = value),
+            ];
+            for (query, metric, set) in queries {
+                let Some(query) = query else {
+                    continue;
+                };
+                if let Ok(result) = self.query_service_prometheus(query).await {
+                    self.update_service_value(service, &result, metric, set);
+                }
+            }
+        }
+
+        Ok(services)
     }
 
-    async fn fetch_service_metrics(&self) -> Result<HashMap<String, ServiceMetrics>> {
+    fn fallback_services() -> HashMap<String, ServiceMetrics> {
         let mut services = HashMap::new();
 
-        // Start with fallback mock data
+        // Fallback mock data
         services.insert("n8n-0".to_string(), ServiceMetrics {
             name: "n8n-0".to_string(),
             namespace: "homelab".to_string(),
@@ -208,6 +451,11 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
         services.insert("postgres-0".to_string(), ServiceMetrics {
@@ -228,6 +476,11 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
         services.insert("redis-0".to_string(), ServiceMetrics {
@@ -248,6 +501,11 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
         services.insert("prometheus-0".to_string(), ServiceMetrics {
@@ -268,6 +526,11 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
         services.insert("grafana-0".to_string(), ServiceMetrics {
@@ -288,6 +551,11 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
         services.insert("qdrant-0".to_string(), ServiceMetrics {
@@ -308,6 +576,11 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
         services.insert("flowise-0".to_string(), ServiceMetrics {
@@ -328,120 +601,449 @@ impl PrometheusClient {
             last_health_check: 0,
             health_response_time: 0.0,
             consecutive_failures: 0,
+            image: "unknown".to_string(),
+            cert_expires_at: None,
+            age: 0,
+            events: Vec::new(),
+            stale_metrics: HashSet::new(),
         });
 
-        // Try to get real service status from Prometheus
-        if let Ok(up_result) = self.query_prometheus("up{job=\"postgres\"}").await {
-            self.update_service_status(&mut services, &up_result, "postgres-0");
-        }
+        services
+    }
 
-        if let Ok(up_result) = self.query_prometheus("up{job=\"n8n\"}").await {
-            self.update_service_status(&mut services, &up_result, "n8n-0");
-        }
+    async fn query_prometheus(&self, query: &str) -> Result<PrometheusResponse> {
+        self.query_prometheus_at(&self.config.url, query).await
+    }
+
+    /// Query the service Prometheus, falling back to the main one
+    async fn query_service_prometheus(&self, query: &str) -> Result<PrometheusResponse> {
+        let base_url = self.config.service_prometheus_url.as_deref().unwrap_or(&self.config.url);
+        self.query_prometheus_at(base_url, query).await
+    }
+
+    async fn query_prometheus_at(&self, base_url: &str, query: &str) -> Result<PrometheusResponse> {
+        let started = Instant::now();
+        let expanded = with_label_selectors(query, &self.label_selectors);
+        let result = self.send_query(&format!("{}/api/v1/query", base_url), &[("query", expanded)]).await;
+        self.record_query(query, started.elapsed(), &result);
+        result
+    }
 
-        if let Ok(up_result) = self.query_prometheus("up{job=\"redis\"}").await {
-            self.update_service_status(&mut services, &up_result, "redis-0");
+    fn record_query(&self, query: &str, latency: Duration, result: &Result<PrometheusResponse>) {
+        if self.log_queries {
+            match result {
+                Ok(response) => tracing::info!("Query {} returned {} series in {:?}", query, response.data.result.len(), latency),
+                Err(e) => tracing::warn!("Query {} failed after {:?}: {:#}", query, latency, e),
+            }
         }
+        let mut stats = self.query_stats.lock().unwrap_or_else(PoisonError::into_inner);
+        let stats = stats.entry(query.to_string()).or_default();
+        stats.last_ok = result.is_ok();
+        stats.last_latency = latency;
+        match result {
+            Ok(_) => stats.successes += 1,
+            Err(e) => {
+                stats.failures += 1;
+                stats.last_error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// Raw result of an arbitrary instant query, as one `name{labels} value`
+    /// line per series
+    pub async fn scratchpad_query(&self, query: &str) -> Result<Vec<String>> {
+        let response = self.query_prometheus(query).await?;
+        Ok(response
+            .data
+            .result
+            .iter()
+            .map(|series| {
+                let name = series.metric.get("__name__").map(String::as_str).unwrap_or_default();
+                let mut labels: Vec<String> = series.metric
+                    .iter()
+                    .filter(|(label, _)| label.as_str() != "__name__")
+                    .map(|(label, value)| format!("{}=\"{}\"", label, value))
+                    .collect();
+                labels.sort();
+                let value = series.value().map_or_else(|| "NaN".to_string(), |value| value.to_string());
+                format!("{}{{{}}} {}", name, labels.join(", "), value)
+            })
+            .collect())
+    }
+
+    /// Outcomes of every query sent so far, most failures first
+    pub fn query_stats(&self) -> Vec<(String, QueryStats)> {
+        let stats = self.query_stats.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut stats: Vec<_> = stats.iter().map(|(query, stats)| (query.clone(), stats.clone())).collect();
+        stats.sort_by(|(a_query, a), (b_query, b)| b.failures.cmp(&a.failures).then_with(|| a_query.cmp(b_query)));
+        stats
+    }
+
+    /// Samples of `query` every `step` seconds between the Unix times
+    /// `start` and `end`
+    async fn query_prometheus_range(&self, query: &str, start: f64, end: f64, step: f64) -> Result<PrometheusResponse> {
+        self.query_prometheus_range_at(&self.config.url, query, start, end, step).await
+    }
+
+    /// Range query against the service Prometheus, falling back to the main one
+    async fn query_service_prometheus_range(&self, query: &str, start: f64, end: f64, step: f64) -> Result<PrometheusResponse> {
+        let base_url = self.config.service_prometheus_url.as_deref().unwrap_or(&self.config.url);
+        self.query_prometheus_range_at(base_url, query, start, end, step).await
+    }
+
+    async fn query_prometheus_range_at(&self, base_url: &str, query: &str, start: f64, end: f64, step: f64) -> Result<PrometheusResponse> {
+        let started = Instant::now();
+        let params = [
+            ("query", with_label_selectors(query, &self.label_selectors)),
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("step", step.to_string()),
+        ];
+        let result = self.send_query(&format!("{}/api/v1/query_range", base_url), &params).await;
+        // Kept apart from the instant query of the same expression
+        self.record_query(&format!("range:{}", query), started.elapsed(), &result);
+        result
+    }
+
+    /// Start, end and step of a range query ending now that yields about
+    /// `points` samples over `window_secs`
+    fn history_range(window_secs: u64, points: usize) -> (f64, f64, f64) {
+        let end = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+        let step = (window_secs as f64 / points.max(1) as f64).max(1.0);
+        (end - window_secs as f64, end, step)
+    }
+
+    /// The last `points` values of a range series
+    fn last_range_values(series: &PrometheusMetric, points: usize) -> Vec<f64> {
+        let mut values = series.range_values();
+        // The range is inclusive at both ends, so it can hold one point too many
+        values.drain(..values.len().saturating_sub(points));
+        values
+    }
 
-        if let Ok(up_result) = self.query_prometheus("up{job=\"prometheus\"}").await {
-            self.update_service_status(&mut services, &up_result, "prometheus-0");
+    /// Each node's CPU usage over the last `window_secs`, as `points` evenly
+    /// spaced samples (fewer where Prometheus has no data)
+    pub async fn node_cpu_history(&self, window_secs: u64, points: usize) -> Result<HashMap<String, Vec<f64>>> {
+        let (start, end, step) = Self::history_range(window_secs, points);
+        let result = self.query_prometheus_range(&self.config.node_queries.cpu_usage, start, end, step).await?;
+
+        let mut history = HashMap::new();
+        for series in &result.data.result {
+            let Some(node_name) = series.metric.get("instance").and_then(|instance| self.node_name_for_instance(instance)) else {
+                continue;
+            };
+            history.insert(node_name.to_string(), Self::last_range_values(series, points));
         }
+        Ok(history)
+    }
 
-        Ok(services)
+    /// Each service's CPU usage over the last `window_secs`, like `node_cpu_history`
+    pub async fn service_cpu_history(&self, window_secs: u64, points: usize) -> Result<HashMap<String, Vec<f64>>> {
+        let (start, end, step) = Self::history_range(window_secs, points);
+        let result = self.query_service_prometheus_range(&self.config.service_queries.cpu_usage, start, end, step).await?;
+
+        let mut history = HashMap::new();
+        for series in &result.data.result {
+            if let Some(service_name) = Self::service_for_series(series) {
+                history.insert(service_name.to_string(), Self::last_range_values(series, points));
+            }
+        }
+        Ok(history)
     }
 
-    async fn query_prometheus(&self, query: &str) -> Result<PrometheusResponse> {
-        let url = format!("{}/api/v1/query", self.config.url);
-        let params = [("query", query)];
+    async fn send_query(&self, url: &str, params: &[(&str, String)]) -> Result<PrometheusResponse> {
+        let mut request = self.client.get(url).query(params);
+        if let Some(org_id) = &self.config.org_id {
+            request = request.header("X-Scope-OrgID", org_id);
+        }
+        if let Some(auth) = &self.config.auth {
+            request = match &auth.bearer_token {
+                Some(token) => request.bearer_auth(token),
+                None => request.basic_auth(&auth.username, Some(&auth.password)),
+            };
+        }
 
-        let response = self.client
-            .get(&url)
-            .query(&params)
+        let response = request
             .send()
             .await
             .context("Failed to send request to Prometheus")?;
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Prometheus returned status: {}", response.status()));
-        }
-
-        let prometheus_response: PrometheusResponse = response
-            .json()
+        // Prometheus reports query errors (e.g. bad PromQL) as a JSON body with a
+        // 4xx/5xx status, so parse the body before looking at the HTTP status
+        let http_status = response.status();
+        let body = response
+            .bytes()
             .await
-            .context("Failed to parse Prometheus response")?;
+            .context("Failed to read Prometheus response")?;
 
+        let prometheus_response: PrometheusResponse = match serde_json::from_slice(&body) {
+            Ok(parsed) => parsed,
+            Err(_) if !http_status.is_success() => {
+                return Err(anyhow::anyhow!("Prometheus returned status: {}", http_status));
+            }
+            Err(e) => return Err(e).context("Failed to parse Prometheus response"),
+        };
+
+        if prometheus_response.status != "success" {
+            return Err(anyhow::anyhow!(
+                "Prometheus query failed ({}): {}",
+                prometheus_response.error_type.as_deref().unwrap_or("unknown"),
+                prometheus_response.error.as_deref().unwrap_or("no error message")
+            ));
+        }
+
+        self.successful_queries.fetch_add(1, Ordering::Relaxed);
         Ok(prometheus_response)
     }
 
+    /// Map a Prometheus `instance` label to one of the configured node names
+    fn node_name_for_instance(&self, instance: &str) -> Option<&str> {
+        let host = instance_host(instance);
+        self.node_targets
+            .iter()
+            .find(|(name, address)| host_matches(host, name, address))
+            .map(|(name, _)| name.as_str())
+    }
+
     fn update_node_cpu(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse) {
-        for metric in &result.data.result {
-            if let Some(instance) = metric.metric.get("instance") {
-                let node_name = if instance.contains("100.72.98.106") || instance.contains("pesubuntu") {
-                    "pesubuntu"
-                } else if instance.contains("asuna") {
-                    "asuna"
-                } else {
-                    continue;
-                };
+        self.update_node_value(nodes, result, "cpu_usage", "cpu", |node, value| node.cpu_usage = value);
+    }
 
-                if let Some(node) = nodes.get_mut(node_name) {
-                    node.cpu_usage = metric.value();
-                }
+    fn update_node_gpu(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse) {
+        self.update_node_value(nodes, result, "gpu_usage", "gpu", |node, value| node.gpu_usage = value);
+    }
+
+    fn update_node_disk(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse) {
+        self.update_node_value(nodes, result, "disk_usage", "disk", |node, value| node.disk_usage = value);
+    }
+
+    /// Receive and transmit rates come from separate queries; either may be missing
+    fn update_node_network(&self, nodes: &mut HashMap<String, NodeMetrics>, rx: Option<&PrometheusResponse>, tx: Option<&PrometheusResponse>) {
+        if let Some(result) = rx {
+            self.update_node_value(nodes, result, "network_rx", "network", |node, value| node.network_rx = value);
+        }
+        if let Some(result) = tx {
+            self.update_node_value(nodes, result, "network_tx", "network", |node, value| node.network_tx = value);
+        }
+    }
+
+    fn update_node_temperature(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse) {
+        self.update_node_value(nodes, result, "temperature", "temperature", |node, value| node.temperature = value);
+    }
+
+    /// Set a per-instance metric on each node, applying `on_missing` to the
+    /// rest. Several series for one node are combined with the aggregation
+    /// configured for `query` (see `NodeQueries::aggregation`).
+    fn update_node_value(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse, query: &str, metric: &'static str, set: impl Fn(&mut NodeMetrics, f64)) {
+        let mut values: HashMap<&str, Vec<f64>> = HashMap::new();
+        for metric_result in &result.data.result {
+            let Some(node_name) = metric_result.metric.get("instance").and_then(|instance| self.node_name_for_instance(instance)) else {
+                continue;
+            };
+            let Some(value) = metric_result.value() else {
+                continue;
+            };
+            values.entry(node_name).or_default().push(value);
+        }
+
+        // Nodes usually have several temperature sensors, so those are averaged unless configured
+        let aggregation = self.config.node_queries.aggregation.get(query).copied().unwrap_or(if query == "temperature" {
+            Aggregation::Avg
+        } else {
+            Aggregation::Last
+        });
+        let mut seen = HashSet::new();
+        for (node_name, values) in values {
+            let Some(value) = aggregation.apply(&values) else {
+                continue;
+            };
+            if let Some(node) = nodes.get_mut(node_name) {
+                set(node, value);
+                node.stale_metrics.remove(metric);
+                node.unavailable_metrics.remove(metric);
+                seen.insert(node_name);
             }
         }
+
+        self.apply_missing_node_policy(nodes, &seen, metric, |node| set(node, 0.0));
     }
 
-    fn update_node_memory(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse) {
-        for metric in &result.data.result {
-            if let Some(instance) = metric.metric.get("instance") {
-                let node_name = if instance.contains("100.72.98.106") || instance.contains("pesubuntu") {
-                    "pesubuntu"
-                } else if instance.contains("asuna") {
-                    "asuna"
-                } else {
-                    continue;
-                };
+    /// Service a series belongs to: the first whose job name appears in the
+    /// series' `name` (cAdvisor) or `pod` label
+    fn service_for_series(series: &PrometheusMetric) -> Option<&'static str> {
+        let name = series.metric.get("name").or_else(|| series.metric.get("pod"))?;
+        SERVICE_JOBS.iter().find(|(job, _)| name.contains(job)).map(|(_, service)| *service)
+    }
 
-                if let Some(node) = nodes.get_mut(node_name) {
-                    node.memory_usage = metric.value();
-                }
+    /// Set one metric on every service with a series in `result`, applying
+    /// `on_missing` to the rest
+    fn update_service_metric(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse, metric: &'static str, set: ServiceSetter) {
+        let mut seen = HashSet::new();
+        for series in &result.data.result {
+            let (Some(service_name), Some(value)) = (Self::service_for_series(series), series.value()) else {
+                continue;
+            };
+            if let Some(service) = services.get_mut(service_name) {
+                set(service, value);
+                service.stale_metrics.remove(metric);
+                seen.insert(service_name);
+            }
+        }
+
+        for (name, service) in services.iter_mut() {
+            if !seen.contains(name.as_str()) {
+                self.apply_missing_service_policy(service, metric, |service| set(service, 0.0));
             }
         }
     }
 
     fn update_service_cpu(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
-        for metric in &result.data.result {
-            if let Some(name) = metric.metric.get("name") {
-                let service_name = if name.contains("n8n") {
-                    "n8n-0"
-                } else if name.contains("postgres") {
-                    "postgres-0"
-                } else if name.contains("redis") {
-                    "redis-0"
-                } else if name.contains("prometheus") {
-                    "prometheus-0"
-                } else if name.contains("grafana") {
-                    "grafana-0"
-                } else if name.contains("qdrant") {
-                    "qdrant-0"
-                } else if name.contains("flowise") {
-                    "flowise-0"
-                } else {
-                    continue;
-                };
+        self.update_service_metric(services, result, "cpu", |service, value| service.cpu_usage = value);
+    }
+
+    fn update_service_memory(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        self.update_service_metric(services, result, "memory", |service, value| service.memory_usage = value);
+    }
+
+    fn update_service_rps(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        self.update_service_metric(services, result, "rps", |service, value| service.requests_per_sec = value);
+    }
+
+    fn update_service_latency(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        self.update_service_metric(services, result, "latency", |service, value| service.response_time = value);
+    }
+
+    fn update_service_error_rate(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        self.update_service_metric(services, result, "errors", |service, value| service.error_rate = value);
+    }
+
+    fn update_service_status(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse, service_name: &str) {
+        let Some(service) = services.get_mut(service_name) else {
+            return;
+        };
+
+        if let Some(value) = result.data.result.first().and_then(PrometheusMetric::value) {
+            let is_up = value == 1.0;
+            service.status = if is_up { "Running".to_string() } else { "Stopped".to_string() };
+            service.stale_metrics.remove("status");
+        } else {
+            self.apply_missing_service_policy(service, "status", |service| service.status = "Unknown".to_string());
+        }
+    }
+
+    /// Set each service's image from the `image` label of its pod's series
+    fn update_service_images(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        for service in services.values_mut() {
+            service.image = result.data.result
+                .iter()
+                .find(|metric| metric.metric.get("pod") == Some(&service.name))
+                .and_then(|metric| metric.metric.get("image"))
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string());
+        }
+    }
+
+    /// Set each service's age from its pod's creation timestamp
+    fn update_service_ages(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+        for service in services.values_mut() {
+            service.age = result.data.result
+                .iter()
+                .find(|metric| metric.metric.get("pod") == Some(&service.name))
+                .and_then(|metric| metric.value())
+                .map(|created| (now - created).max(0.0) as u64)
+                .unwrap_or(0);
+        }
+    }
+
+    /// Rank each node's process groups by CPU. Series are matched to nodes by
+    /// `instance` and named by their `groupname` label (process-exporter's).
+    fn update_node_processes(&self, nodes: &mut HashMap<String, NodeMetrics>, result: &PrometheusResponse) {
+        let mut processes: HashMap<&str, Vec<ProcessUsage>> = HashMap::new();
+        for series in &result.data.result {
+            let Some(node_name) = series.metric.get("instance").and_then(|instance| self.node_name_for_instance(instance)) else {
+                continue;
+            };
+            let (Some(name), Some(cpu_usage)) = (series.metric.get("groupname"), series.value()) else {
+                continue;
+            };
+            processes.entry(node_name).or_default().push(ProcessUsage { name: name.clone(), cpu_usage });
+        }
+        for (name, node) in nodes.iter_mut() {
+            let mut top = processes.remove(name.as_str()).unwrap_or_default();
+            top.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage).then_with(|| a.name.cmp(&b.name)));
+            node.top_processes = top;
+        }
+    }
+
+    /// Attach event series to the service whose pod they involve (the
+    /// `involved_object_name` or `pod` label); `type`, `reason` and the
+    /// optional `message` label describe the event, the value is its count
+    fn update_service_events(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse) {
+        for service in services.values_mut() {
+            let mut events: Vec<ServiceEvent> = result.data.result
+                .iter()
+                .filter(|metric| {
+                    metric.metric.get("involved_object_name").or_else(|| metric.metric.get("pod")) == Some(&service.name)
+                })
+                .filter_map(|metric| {
+                    let count = metric.value()?;
+                    let label = |name: &str| metric.metric.get(name).cloned().unwrap_or_default();
+                    (count > 0.0).then(|| ServiceEvent {
+                        kind: label("type"),
+                        reason: label("reason"),
+                        message: label("message"),
+                        count: count.round() as u64,
+                    })
+                })
+                .collect();
+            // Warnings first, then the most frequent
+            events.sort_by(|a, b| b.is_warning().cmp(&a.is_warning()).then(b.count.cmp(&a.count)));
+            service.events = events;
+        }
+    }
 
-                if let Some(service) = services.get_mut(service_name) {
-                    service.cpu_usage = metric.value();
+    /// Apply `on_missing` to every node the query returned no series for.
+    /// Nodes that never had a series for the metric are marked unavailable instead.
+    fn apply_missing_node_policy(&self, nodes: &mut HashMap<String, NodeMetrics>, seen: &HashSet<&str>, metric: &'static str, clear: impl Fn(&mut NodeMetrics)) {
+        let mut reported = self.reported_metrics.lock().unwrap_or_else(PoisonError::into_inner);
+        reported.extend(seen.iter().map(|name| (name.to_string(), metric)));
+        for (name, node) in nodes.iter_mut() {
+            if seen.contains(name.as_str()) {
+                continue;
+            }
+            if !reported.contains(&(name.clone(), metric)) {
+                node.unavailable_metrics.insert(metric);
+                continue;
+            }
+            match self.config.on_missing {
+                MissingDataPolicy::Retain => {}
+                MissingDataPolicy::Clear => clear(node),
+                MissingDataPolicy::MarkStale => {
+                    node.stale_metrics.insert(metric);
                 }
             }
         }
     }
 
-    fn update_service_status(&self, services: &mut HashMap<String, ServiceMetrics>, result: &PrometheusResponse, service_name: &str) {
-        if let Some(metric) = result.data.result.first() {
-            let is_up = metric.value() == 1.0;
-            if let Some(service) = services.get_mut(service_name) {
-                service.status = if is_up { "Running".to_string() } else { "Stopped".to_string() };
+    /// Set one metric of a service from a query written for that service alone
+    fn update_service_value(&self, service: &mut ServiceMetrics, result: &PrometheusResponse, metric: &'static str, set: ServiceSetter) {
+        match result.data.result.first().and_then(PrometheusMetric::value) {
+            Some(value) => {
+                set(service, value);
+                service.stale_metrics.remove(metric);
+            }
+            None => self.apply_missing_service_policy(service, metric, |service| set(service, 0.0)),
+        }
+    }
+
+    /// Apply `on_missing` to a service the query returned no series for
+    fn apply_missing_service_policy(&self, service: &mut ServiceMetrics, metric: &'static str, clear: impl Fn(&mut ServiceMetrics)) {
+        match self.config.on_missing {
+            MissingDataPolicy::Retain => {}
+            MissingDataPolicy::Clear => clear(service),
+            MissingDataPolicy::MarkStale => {
+                service.stale_metrics.insert(metric);
             }
         }
     }
@@ -451,4 +1053,668 @@ impl PrometheusClient {
         let result = self.query_prometheus("up").await?;
         Ok(!result.data.result.is_empty())
     }
-}
\ No newline at end of file
+}
+/// Base URL without trailing slashes, so API paths can be appended
+fn normalize_base_url(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+/// Host part of an `instance` label: drops a `:port` and the brackets
+/// around IPv6 addresses (`[::1]:9100` -> `::1`)
+fn instance_host(instance: &str) -> &str {
+    if let Some(rest) = instance.strip_prefix('[') {
+        return rest.split_once(']').map_or(rest, |(host, _)| host);
+    }
+    match instance.rsplit_once(':') {
+        // More than one colon without brackets is a bare IPv6 address
+        Some((host, port)) if !host.contains(':') && port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => instance,
+    }
+}
+
+/// Whether an instance host refers to a node, by IP (in any notation),
+/// hostname or the first label of an FQDN
+fn host_matches(host: &str, name: &str, address: &str) -> bool {
+    if let (Ok(host_ip), Ok(address_ip)) = (host.parse::<IpAddr>(), address.parse::<IpAddr>()) {
+        return host_ip == address_ip;
+    }
+    let short_name = host.split('.').next().unwrap_or(host);
+    host.eq_ignore_ascii_case(address) || host.eq_ignore_ascii_case(name) || short_name.eq_ignore_ascii_case(name)
+}
+
+/// Keywords followed by a parenthesised list of label names
+const LABEL_LIST_KEYWORDS: [&str; 6] = ["by", "without", "on", "ignoring", "group_left", "group_right"];
+
+/// Words that can't be metric names when not followed by '(' or '{'
+const PROMQL_KEYWORDS: [&str; 7] = ["and", "or", "unless", "bool", "offset", "inf", "nan"];
+
+/// Add `label="value"` matchers to every metric selector in a PromQL query,
+/// e.g. `rate(x[5m])` becomes `rate(x{env="prod"}[5m])`. A label the selector
+/// already matches on is left as written.
+fn with_label_selectors(query: &str, selectors: &[(String, String)]) -> String {
+    if selectors.is_empty() {
+        return query.to_string();
+    }
+
+    let bytes = query.as_bytes();
+    let is_word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b':';
+    let mut out = String::with_capacity(query.len() + 32);
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' | b'\'' | b'`' => {
+                let end = string_end(bytes, i);
+                out.push_str(&query[i..end]);
+                i = end;
+            }
+            // Range or subquery duration
+            b'[' => {
+                let end = query[i..].find(']').map_or(bytes.len(), |offset| i + offset + 1);
+                out.push_str(&query[i..end]);
+                i = end;
+            }
+            // Selector without a metric name, e.g. {__name__=~"node_.*"}
+            b'{' => i = push_selector(query, i, selectors, &mut out),
+            // Numbers and durations (5m, 1e3, 0x1f)
+            b if b.is_ascii_digit() || b == b'.' => {
+                let start = i;
+                while i < bytes.len() && (is_word_byte(bytes[i]) || bytes[i] == b'.') {
+                    i += 1;
+                }
+                out.push_str(&query[start..i]);
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' || b == b':' => {
+                let start = i;
+                while i < bytes.len() && is_word_byte(bytes[i]) {
+                    i += 1;
+                }
+                let word = &query[start..i];
+                out.push_str(word);
+
+                let next = i + query[i..].len() - query[i..].trim_start().len();
+                let next_word_len = query[next..].bytes().take_while(|b| is_word_byte(*b)).count();
+                let next_word = &query[next..next + next_word_len];
+                let is_keyword = |keywords: &[&str]| keywords.iter().any(|keyword| word.eq_ignore_ascii_case(keyword));
+                match bytes.get(next) {
+                    Some(b'(') if is_keyword(&LABEL_LIST_KEYWORDS) => {
+                        let end = query[next..].find(')').map_or(bytes.len(), |offset| next + offset + 1);
+                        out.push_str(&query[i..end]);
+                        i = end;
+                    }
+                    // Function call
+                    Some(b'(') => {}
+                    Some(b'{') => {
+                        out.push_str(&query[i..next]);
+                        i = push_selector(query, next, selectors, &mut out);
+                    }
+                    _ if is_keyword(&PROMQL_KEYWORDS) || is_keyword(&LABEL_LIST_KEYWORDS) => {}
+                    // Aggregation with a leading clause, e.g. `sum by (job) (...)`
+                    _ if next_word.eq_ignore_ascii_case("by") || next_word.eq_ignore_ascii_case("without") => {}
+                    _ => {
+                        out.push('{');
+                        out.push_str(&format_matchers(selectors, &[]));
+                        out.push('}');
+                    }
+                }
+            }
+            _ => {
+                let c = query[i..].chars().next().unwrap_or_default();
+                out.push(c);
+                i += c.len_utf8().max(1);
+            }
+        }
+    }
+    out
+}
+
+/// Copy the `{...}` selector starting at `open` with the extra matchers
+/// added, returning the index just past it
+fn push_selector(query: &str, open: usize, selectors: &[(String, String)], out: &mut String) -> usize {
+    let bytes = query.as_bytes();
+    let mut existing = Vec::new();
+    let mut i = open + 1;
+    let mut close = bytes.len();
+    while i < bytes.len() {
+        match bytes[i] {
+            b'}' => {
+                close = i;
+                break;
+            }
+            b'"' | b'\'' | b'`' => i = string_end(bytes, i),
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                existing.push(&query[start..i]);
+            }
+            _ => i += 1,
+        }
+    }
+
+    let inner = query[open + 1..close].trim_end();
+    let added = format_matchers(selectors, &existing);
+    out.push('{');
+    out.push_str(inner);
+    if !inner.is_empty() && !inner.ends_with(',') && !added.is_empty() {
+        out.push(',');
+    }
+    out.push_str(&added);
+    out.push('}');
+    (close + 1).min(bytes.len())
+}
+
+/// `a="1",b="2"` for the selectors whose label isn't in `skip`
+fn format_matchers(selectors: &[(String, String)], skip: &[&str]) -> String {
+    selectors
+        .iter()
+        .filter(|(label, _)| !skip.contains(&label.as_str()))
+        .map(|(label, value)| format!("{}=\"{}\"", label, value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Index just past the string literal starting at `start`
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && quote != b'`' {
+            i += 2;
+            continue;
+        }
+        if bytes[i] == quote {
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{NodeConfig, NodeQueries, PrometheusAuth};
+    use serde_json::json;
+    use std::sync::Arc;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const CPU_QUERY: &str = "100 - (avg by (instance) (irate(node_cpu_seconds_total{mode=\"idle\"}[5m])) * 100)";
+
+    fn client_for(server: &MockServer) -> PrometheusClient {
+        client_with(PrometheusConfig {
+            url: server.uri(),
+            ..PrometheusConfig::default()
+        })
+    }
+
+    /// Client for `config`, targeting the default nodes
+    fn client_with(config: PrometheusConfig) -> PrometheusClient {
+        let targets = NodeConfig::default().nodes.into_iter().map(|node| (node.name, node.address)).collect();
+        PrometheusClient::new(config, targets, Vec::new(), false).unwrap()
+    }
+
+    /// Instant-vector response with one sample per (label value, value) pair
+    fn vector(label: &str, samples: &[(&str, &str)]) -> serde_json::Value {
+        let result: Vec<_> = samples
+            .iter()
+            .map(|(name, value)| json!({ "metric": { label: name }, "value": [1733318400.0, value] }))
+            .collect();
+        json!({ "status": "success", "data": { "resultType": "vector", "result": result } })
+    }
+
+    async fn mount_query(server: &MockServer, query: &str, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query"))
+            .and(query_param("query", query))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn query_prometheus_parses_vector() {
+        let server = MockServer::start().await;
+        mount_query(&server, "up", ResponseTemplate::new(200).set_body_json(vector("instance", &[("asuna:9100", "1")]))).await;
+
+        let response = client_for(&server).query_prometheus("up").await.unwrap();
+
+        assert_eq!(response.data.result_type, "vector");
+        assert_eq!(response.data.result.len(), 1);
+        assert_eq!(response.data.result[0].value(), Some(1.0));
+    }
+
+    fn metric_with_value(value: serde_json::Value) -> PrometheusMetric {
+        PrometheusMetric {
+            metric: HashMap::new(),
+            value: serde_json::from_value(value).unwrap(),
+            values: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn value_parses_numbers_and_strings() {
+        assert_eq!(metric_with_value(json!([1733318400.0, "42.5"])).value(), Some(42.5));
+        assert_eq!(metric_with_value(json!([1733318400.0, 0])).value(), Some(0.0));
+    }
+
+    #[test]
+    fn value_rejects_malformed_arrays() {
+        assert_eq!(metric_with_value(json!([])).value(), None);
+        assert_eq!(metric_with_value(json!([1733318400.0])).value(), None);
+        assert_eq!(metric_with_value(json!([1733318400.0, "1", "2"])).value(), None);
+        assert_eq!(metric_with_value(json!([1733318400.0, "not a number"])).value(), None);
+    }
+
+    #[test]
+    fn value_rejects_nan() {
+        assert_eq!(metric_with_value(json!([1733318400.0, "NaN"])).value(), None);
+        assert_eq!(metric_with_value(json!([1733318400.0, "+Inf"])).value(), None);
+    }
+
+    #[tokio::test]
+    async fn update_node_cpu_skips_unparseable_values() {
+        let server = MockServer::start().await;
+        let body = vector("instance", &[("asuna:9100", "NaN")]);
+        mount_query(&server, CPU_QUERY, ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let client = client_for(&server);
+        let mut nodes = PrometheusClient::fallback_nodes();
+        let response = client.query_prometheus(CPU_QUERY).await.unwrap();
+        client.update_node_cpu(&mut nodes, &response);
+
+        // Retained rather than zeroed
+        assert_eq!(nodes["asuna"].cpu_usage, 42.0);
+    }
+
+    #[tokio::test]
+    async fn query_prometheus_reports_query_errors() {
+        let server = MockServer::start().await;
+        let body = json!({ "status": "error", "errorType": "bad_data", "error": "parse error at char 5" });
+        mount_query(&server, "up{", ResponseTemplate::new(400).set_body_json(body)).await;
+
+        let error = client_for(&server).query_prometheus("up{").await.unwrap_err();
+
+        assert_eq!(error.to_string(), "Prometheus query failed (bad_data): parse error at char 5");
+    }
+
+    #[tokio::test]
+    async fn query_stats_count_outcomes_per_query() {
+        let server = MockServer::start().await;
+        mount_query(&server, "up", ResponseTemplate::new(200).set_body_json(vector("instance", &[("asuna:9100", "1")]))).await;
+        mount_query(&server, "broken", ResponseTemplate::new(502)).await;
+
+        let client = client_for(&server);
+        for query in ["up", "up", "broken"] {
+            let _ = client.query_prometheus(query).await;
+        }
+
+        let stats = client.query_stats();
+        assert_eq!(stats[0].0, "broken");
+        assert_eq!((stats[0].1.successes, stats[0].1.failures, stats[0].1.last_ok), (0, 1, false));
+        assert_eq!(stats[0].1.last_error.as_deref(), Some("Prometheus returned status: 502 Bad Gateway"));
+        assert_eq!(stats[1].0, "up");
+        assert_eq!((stats[1].1.successes, stats[1].1.failures, stats[1].1.last_ok), (2, 0, true));
+    }
+
+    #[tokio::test]
+    async fn query_prometheus_reports_http_status_without_json() {
+        let server = MockServer::start().await;
+        mount_query(&server, "up", ResponseTemplate::new(502).set_body_string("Bad Gateway")).await;
+
+        let error = client_for(&server).query_prometheus("up").await.unwrap_err();
+
+        assert!(error.to_string().contains("502"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn query_prometheus_sends_org_id() {
+        let server = MockServer::start().await;
+        Mock::given(path("/api/v1/query"))
+            .and(header("X-Scope-OrgID", "homelab"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vector("instance", &[])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_with(PrometheusConfig {
+            url: server.uri(),
+            org_id: Some("homelab".to_string()),
+            ..PrometheusConfig::default()
+        });
+
+        client.query_prometheus("up").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn query_prometheus_sends_auth() {
+        let server = MockServer::start().await;
+        for authorization in ["Basic YWRtaW46c2VjcmV0", "Bearer t0ken"] {
+            Mock::given(path("/api/v1/query"))
+                .and(header("Authorization", authorization))
+                .respond_with(ResponseTemplate::new(200).set_body_json(vector("instance", &[])))
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let basic = PrometheusAuth { username: "admin".to_string(), password: "secret".to_string(), bearer_token: None };
+        let bearer = PrometheusAuth { bearer_token: Some("t0ken".to_string()), ..basic.clone() };
+        for auth in [basic, bearer] {
+            let client = client_with(PrometheusConfig { url: server.uri(), auth: Some(auth), ..PrometheusConfig::default() });
+            client.query_prometheus("up").await.unwrap();
+        }
+    }
+
+    /// Forward connections to `server`, returning the proxy URL and a count
+    /// of the connections it accepted
+    async fn counting_proxy(server: &MockServer) -> (String, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let upstream = *server.address();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut inbound, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    if let Ok(mut outbound) = tokio::net::TcpStream::connect(upstream).await {
+                        let _ = tokio::io::copy_bidirectional(&mut inbound, &mut outbound).await;
+                    }
+                });
+            }
+        });
+        (proxy_url, connections)
+    }
+
+    #[tokio::test]
+    async fn queries_reuse_pooled_connections() {
+        let server = MockServer::start().await;
+        mount_query(&server, "up", ResponseTemplate::new(200).set_body_json(vector("instance", &[]))).await;
+
+        for (max_idle, expected_connections) in [(4, 1), (0, 5)] {
+            let (proxy_url, connections) = counting_proxy(&server).await;
+            let client = client_with(PrometheusConfig {
+                url: proxy_url,
+                pool_max_idle_per_host: max_idle,
+                ..PrometheusConfig::default()
+            });
+
+            for _ in 0..5 {
+                client.query_prometheus("up").await.unwrap();
+            }
+            assert_eq!(connections.load(Ordering::SeqCst), expected_connections, "pool_max_idle_per_host: {max_idle}");
+        }
+    }
+
+    #[test]
+    fn label_selectors_are_added_to_every_selector() {
+        let env = [("env".to_string(), "prod".to_string())];
+        let cases = [
+            ("up", r#"up{env="prod"}"#),
+            (
+                CPU_QUERY,
+                r#"100 - (avg by (instance) (irate(node_cpu_seconds_total{mode="idle",env="prod"}[5m])) * 100)"#,
+            ),
+            (
+                "sum(rate(http_requests_total[5m] offset 1h)) by (le)",
+                r#"sum(rate(http_requests_total{env="prod"}[5m] offset 1h)) by (le)"#,
+            ),
+            (
+                "a / on(instance) group_left b",
+                r#"a{env="prod"} / on(instance) group_left b{env="prod"}"#,
+            ),
+            // An explicit matcher for the label wins
+            (r#"kube_pod_info{env="dev"}"#, r#"kube_pod_info{env="dev"}"#),
+            (r#"label_replace(up, "host", "$1", "instance", "(.*):.*")"#, r#"label_replace(up{env="prod"}, "host", "$1", "instance", "(.*):.*")"#),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(with_label_selectors(query, &env), expected);
+        }
+        assert_eq!(with_label_selectors("up", &[]), "up");
+    }
+
+    #[tokio::test]
+    async fn update_node_cpu_matches_instances() {
+        let server = MockServer::start().await;
+        let body = vector("instance", &[("100.72.98.106:9100", "12.5"), ("asuna:9100", "88")]);
+        mount_query(&server, CPU_QUERY, ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let client = client_for(&server);
+        let mut nodes = PrometheusClient::fallback_nodes();
+        let response = client.query_prometheus(CPU_QUERY).await.unwrap();
+        client.update_node_cpu(&mut nodes, &response);
+
+        assert_eq!(nodes["pesubuntu"].cpu_usage, 12.5);
+        assert_eq!(nodes["asuna"].cpu_usage, 88.0);
+    }
+
+    #[tokio::test]
+    async fn update_node_value_aggregates_series_per_node() {
+        let server = MockServer::start().await;
+        let body = vector("instance", &[("asuna:9100", "40"), ("asuna:9100", "90"), ("asuna:9100", "65")]);
+        mount_query(&server, "disk", ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let client = client_with(PrometheusConfig {
+            url: server.uri(),
+            node_queries: NodeQueries {
+                aggregation: HashMap::from([("disk_usage".to_string(), Aggregation::Max)]),
+                ..NodeQueries::default()
+            },
+            ..PrometheusConfig::default()
+        });
+        let mut nodes = PrometheusClient::fallback_nodes();
+        let response = client.query_prometheus("disk").await.unwrap();
+        client.update_node_value(&mut nodes, &response, "disk_usage", "disk", |node, value| node.disk_usage = value);
+        assert_eq!(nodes["asuna"].disk_usage, 90.0);
+        // pesubuntu never had a disk series, so it has no disk rather than stale data
+        assert!(nodes["pesubuntu"].unavailable_metrics.contains("disk"));
+        assert!(nodes["asuna"].unavailable_metrics.is_empty());
+
+        // Unconfigured queries keep the last series
+        client.update_node_value(&mut nodes, &response, "network_rx", "network", |node, value| node.network_rx = value);
+        assert_eq!(nodes["asuna"].network_rx, 65.0);
+    }
+
+    #[tokio::test]
+    async fn service_series_are_matched_by_job_name() {
+        let server = MockServer::start().await;
+        let body = vector("pod", &[("grafana-7d9f-x2", "12.5"), ("flowise-0", "3"), ("unrelated", "99")]);
+        mount_query(&server, "memory", ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let client = client_with(PrometheusConfig {
+            url: server.uri(),
+            on_missing: MissingDataPolicy::MarkStale,
+            ..PrometheusConfig::default()
+        });
+        let mut services = PrometheusClient::fallback_services();
+        let response = client.query_prometheus("memory").await.unwrap();
+        client.update_service_memory(&mut services, &response);
+
+        assert_eq!(services["grafana-0"].memory_usage, 12.5);
+        assert_eq!(services["flowise-0"].memory_usage, 3.0);
+        assert!(services["qdrant-0"].stale_metrics.contains("memory"));
+        assert!(!services["grafana-0"].stale_metrics.contains("memory"));
+    }
+
+    #[tokio::test]
+    async fn temperature_sensors_are_averaged_per_node() {
+        let server = MockServer::start().await;
+        let body = vector("instance", &[("asuna:9100", "50"), ("asuna:9100", "80"), ("asuna:9100", "62")]);
+        mount_query(&server, "node_hwmon_temp_celsius", ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let client = client_for(&server);
+        let mut nodes = PrometheusClient::fallback_nodes();
+        let response = client.query_prometheus("node_hwmon_temp_celsius").await.unwrap();
+        client.update_node_temperature(&mut nodes, &response);
+        assert_eq!(nodes["asuna"].temperature, 64.0);
+        assert!(nodes["pesubuntu"].unavailable_metrics.contains("temperature"));
+    }
+
+    #[tokio::test]
+    async fn process_groups_are_ranked_per_node() {
+        let server = MockServer::start().await;
+        let series = |instance: &str, group: &str, value: &str| {
+            json!({ "metric": { "instance": instance, "groupname": group }, "value": [1733318400.0, value] })
+        };
+        let body = json!({ "status": "success", "data": { "resultType": "vector", "result": [
+            series("asuna:9256", "sshd", "0.5"),
+            series("asuna:9256", "ollama", "182.3"),
+            series("100.72.98.106:9256", "postgres", "12"),
+            series("unknown:9256", "java", "99"),
+        ] } });
+        mount_query(&server, "processes", ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let client = client_with(PrometheusConfig { url: server.uri(), ..PrometheusConfig::default() });
+        let mut nodes = PrometheusClient::fallback_nodes();
+        let response = client.query_prometheus("processes").await.unwrap();
+        client.update_node_processes(&mut nodes, &response);
+
+        let names = |node: &str| nodes[node].top_processes.iter().map(|process| process.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names("asuna"), ["ollama", "sshd"]);
+        assert_eq!(names("pesubuntu"), ["postgres"]);
+    }
+
+    #[tokio::test]
+    async fn base_url_trailing_slash_is_ignored() {
+        let server = MockServer::start().await;
+        mount_query(&server, "up", ResponseTemplate::new(200).set_body_json(vector("instance", &[("asuna:9100", "1")]))).await;
+
+        for url in [server.uri(), format!("{}/", server.uri())] {
+            let client = client_with(PrometheusConfig {
+                url: url.clone(),
+                service_prometheus_url: Some(url.clone()),
+                ..PrometheusConfig::default()
+            });
+            assert_eq!(client.config.url, server.uri());
+            assert!(client.test_connection().await.unwrap(), "query failed for {}", url);
+            assert!(client.query_service_prometheus("up").await.is_ok(), "service query failed for {}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn queries_follow_redirects() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/old/api/v1/query"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", format!("{}/api/v1/query?query=up", server.uri())))
+            .mount(&server)
+            .await;
+        mount_query(&server, "up", ResponseTemplate::new(200).set_body_json(vector("instance", &[("asuna:9100", "1")]))).await;
+
+        let client = client_with(PrometheusConfig {
+            url: format!("{}/old", server.uri()),
+            ..PrometheusConfig::default()
+        });
+        assert!(client.test_connection().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn node_cpu_history_reads_range_values() {
+        let server = MockServer::start().await;
+        let body = json!({ "status": "success", "data": { "resultType": "matrix", "result": [
+            { "metric": { "instance": "asuna:9100" }, "values": [[1.0, "10"], [2.0, "NaN"], [3.0, "30"], [4.0, "40"]] },
+        ] } });
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query_range"))
+            .and(query_param("query", CPU_QUERY))
+            .and(query_param("step", "100"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let history = client.node_cpu_history(300, 3).await.unwrap();
+        assert_eq!(history["asuna"], vec![10.0, 30.0, 40.0]);
+        let stats = client.query_stats();
+        assert_eq!(stats[0].0, format!("range:{}", CPU_QUERY));
+        assert_eq!((stats[0].1.successes, stats[0].1.last_ok), (1, true));
+
+        // Trimmed to the requested number of points
+        let history = client_for(&server).node_cpu_history(200, 2).await.unwrap();
+        assert_eq!(history["asuna"], vec![30.0, 40.0]);
+    }
+
+    #[tokio::test]
+    async fn service_cpu_history_is_keyed_by_service() {
+        let server = MockServer::start().await;
+        let body = json!({ "status": "success", "data": { "resultType": "matrix", "result": [
+            { "metric": { "pod": "redis-master-0" }, "values": [[1.0, "5"], [2.0, "7"]] },
+            { "metric": { "pod": "unrelated-0" }, "values": [[1.0, "1"]] },
+        ] } });
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query_range"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&server)
+            .await;
+
+        let history = client_for(&server).service_cpu_history(60, 60).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history["redis-0"], vec![5.0, 7.0]);
+    }
+
+    #[test]
+    fn instance_host_strips_ports_and_brackets() {
+        assert_eq!(instance_host("10.0.0.1:9100"), "10.0.0.1");
+        assert_eq!(instance_host("[::1]:9100"), "::1");
+        assert_eq!(instance_host("node.example.com:9100"), "node.example.com");
+        assert_eq!(instance_host("fe80::1"), "fe80::1");
+        assert_eq!(instance_host("asuna"), "asuna");
+    }
+
+    #[tokio::test]
+    async fn update_node_cpu_matches_ipv6_and_fqdn_instances() {
+        let server = MockServer::start().await;
+        let body = vector("instance", &[("[::1]:9100", "10"), ("node.example.com:9100", "20"), ("10.0.0.1:9100", "30")]);
+        mount_query(&server, CPU_QUERY, ResponseTemplate::new(200).set_body_json(body)).await;
+
+        let targets = vec![
+            ("pesubuntu".to_string(), "0:0:0:0:0:0:0:1".to_string()),
+            ("node".to_string(), "192.168.1.20".to_string()),
+            ("asuna".to_string(), "10.0.0.1".to_string()),
+        ];
+        let config = PrometheusConfig { url: server.uri(), ..PrometheusConfig::default() };
+        let client = PrometheusClient::new(config, targets, Vec::new(), false).unwrap();
+        let mut nodes = PrometheusClient::fallback_nodes();
+        nodes.insert("node".to_string(), NodeMetrics { name: "node".to_string(), ..nodes["asuna"].clone() });
+        let response = client.query_prometheus(CPU_QUERY).await.unwrap();
+        client.update_node_cpu(&mut nodes, &response);
+
+        assert_eq!(nodes["pesubuntu"].cpu_usage, 10.0);
+        assert_eq!(nodes["node"].cpu_usage, 20.0);
+        assert_eq!(nodes["asuna"].cpu_usage, 30.0);
+    }
+
+    #[tokio::test]
+    async fn update_service_status_maps_up_values() {
+        let server = MockServer::start().await;
+        mount_query(&server, "up{job=\"postgres\"}", ResponseTemplate::new(200).set_body_json(vector("job", &[("postgres", "0")]))).await;
+        mount_query(&server, "up{job=\"redis\"}", ResponseTemplate::new(200).set_body_json(vector("job", &[]))).await;
+
+        let client = client_with(PrometheusConfig {
+            url: server.uri(),
+            on_missing: MissingDataPolicy::Clear,
+            ..PrometheusConfig::default()
+        });
+        let mut services = PrometheusClient::fallback_services();
+
+        let response = client.query_prometheus("up{job=\"postgres\"}").await.unwrap();
+        client.update_service_status(&mut services, &response, "postgres-0");
+        let response = client.query_prometheus("up{job=\"redis\"}").await.unwrap();
+        client.update_service_status(&mut services, &response, "redis-0");
+
+        assert_eq!(services["postgres-0"].status, "Stopped");
+        assert_eq!(services["redis-0"].status, "Unknown");
+    }
+
+    #[tokio::test]
+    async fn update_metrics_keeps_fallback_when_server_is_down() {
+        let server = MockServer::start().await;
+        let mut client = client_for(&server);
+        drop(server);
+
+        assert!(client.update_metrics().await.is_err());
+        assert_eq!(client.get_nodes()["pesubuntu"].cpu_usage, 25.0);
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+use crossterm::event::KeyCode;
+use tokio::sync::oneshot;
+
+/// Recent queries kept for recall with ↑/↓
+const HISTORY_LIMIT: usize = 20;
+
+/// Series lines of a scratchpad query, or its error
+pub type ScratchpadResult = Result<Vec<String>, String>;
+
+/// The ':' PromQL console: an input line and the raw result of the last query
+#[derive(Default)]
+pub struct Scratchpad {
+    pub open: bool,
+    pub input: String,
+    /// Most recent first
+    history: VecDeque<String>,
+    // Position in `history` while recalling with ↑/↓
+    recalled: Option<usize>,
+    /// Query and outcome of the last submission
+    pub result: Option<(String, ScratchpadResult)>,
+    pending: Option<(String, oneshot::Receiver<ScratchpadResult>)>,
+}
+
+impl Scratchpad {
+    pub fn open(&mut self) {
+        self.open = true;
+        self.input.clear();
+        self.recalled = None;
+    }
+
+    /// Handle a key while the scratchpad is open; returns the query to run
+    /// when Enter submits one
+    pub fn key(&mut self, key: KeyCode) -> Option<String> {
+        match key {
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Up => self.recall(self.recalled.map_or(0, |i| i + 1)),
+            KeyCode::Down => match self.recalled {
+                Some(0) | None => {
+                    self.recalled = None;
+                    self.input.clear();
+                }
+                Some(i) => self.recall(i - 1),
+            },
+            KeyCode::Enter => {
+                let query = self.input.trim().to_string();
+                if query.is_empty() {
+                    return None;
+                }
+                self.history.retain(|previous| *previous != query);
+                self.history.push_front(query.clone());
+                self.history.truncate(HISTORY_LIMIT);
+                self.recalled = None;
+                return Some(query);
+            }
+            KeyCode::Esc => self.open = false,
+            _ => {}
+        }
+        None
+    }
+
+    fn recall(&mut self, index: usize) {
+        if let Some(query) = self.history.get(index) {
+            self.input = query.clone();
+            self.recalled = Some(index);
+        }
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = &String> {
+        self.history.iter()
+    }
+
+    /// The query still waiting for its result, if any
+    pub fn running(&self) -> Option<&str> {
+        self.pending.as_ref().map(|(query, _)| query.as_str())
+    }
+
+    pub fn start(&mut self, query: String, receiver: oneshot::Receiver<ScratchpadResult>) {
+        self.pending = Some((query, receiver));
+    }
+
+    /// Take the result of the running query if it has finished
+    pub fn poll(&mut self) {
+        let Some((query, receiver)) = self.pending.as_mut() else {
+            return;
+        };
+        let outcome = match receiver.try_recv() {
+            Ok(outcome) => outcome,
+            Err(oneshot::error::TryRecvError::Empty) => return,
+            Err(oneshot::error::TryRecvError::Closed) => Err("Query task ended without a result".to_string()),
+        };
+        self.result = Some((std::mem::take(query), outcome));
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submit(scratchpad: &mut Scratchpad, query: &str) -> Option<String> {
+        scratchpad.input = query.to_string();
+        scratchpad.key(KeyCode::Enter)
+    }
+
+    #[test]
+    fn history_recalls_recent_queries_without_duplicates() {
+        let mut scratchpad = Scratchpad::default();
+        scratchpad.open();
+        assert_eq!(submit(&mut scratchpad, "  "), None);
+        assert_eq!(submit(&mut scratchpad, " up "), Some("up".to_string()));
+        submit(&mut scratchpad, "node_load1");
+        submit(&mut scratchpad, "up");
+        for i in 0..HISTORY_LIMIT {
+            submit(&mut scratchpad, &format!("q{}", i));
+        }
+        assert_eq!(scratchpad.history().count(), HISTORY_LIMIT);
+
+        scratchpad.input.clear();
+        scratchpad.key(KeyCode::Up);
+        scratchpad.key(KeyCode::Up);
+        assert_eq!(scratchpad.input, format!("q{}", HISTORY_LIMIT - 2));
+        scratchpad.key(KeyCode::Down);
+        assert_eq!(scratchpad.input, format!("q{}", HISTORY_LIMIT - 1));
+        scratchpad.key(KeyCode::Down);
+        assert_eq!(scratchpad.input, "");
+        scratchpad.key(KeyCode::Esc);
+        assert!(!scratchpad.open);
+    }
+}
@@ -1,6 +1,7 @@
 use ratatui::{
     style::Color,
 };
+use crate::config::ColorConfig;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Theme {
@@ -42,6 +43,24 @@ impl Theme {
         }
     }
 
+    /// Theme for a `general.theme` / `ui.auto_theme` name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "dark" => Theme::Dracula,
+            "light" => Theme::SolarizedDark,
+            "default" => Theme::Default,
+            "dracula" => Theme::Dracula,
+            "gruvbox" => Theme::GruvboxDark,
+            "nord" => Theme::Nord,
+            "solarized" => Theme::SolarizedDark,
+            "cyberpunk" => Theme::Cyberpunk,
+            "monokai" => Theme::Monokai,
+            "onedark" => Theme::OneDark,
+            "tokyo" => Theme::TokyoNight,
+            _ => return None,
+        })
+    }
+
     pub fn next(self) -> Self {
         let current = Self::ALL.iter().position(|&t| t == self).unwrap_or(0);
         Self::ALL[(current + 1) % Self::ALL.len()]
@@ -64,11 +83,16 @@ pub struct ThemeColors {
     pub background: Color,
     pub foreground: Color,
     pub text_muted: Color,
+    // Kept in every palette for reference, though selections use selection_*
+    #[allow(dead_code)]
     pub highlight: Color,
     pub border: Color,
     pub gauge_good: Color,
     pub gauge_warning: Color,
     pub gauge_danger: Color,
+    // Selected table row; highlight can be too bright to put text on
+    pub selection_fg: Color,
+    pub selection_bg: Color,
 }
 
 impl ThemeColors {
@@ -86,6 +110,37 @@ impl ThemeColors {
         }
     }
 
+    /// Replace colors set in `ui.colors`. Invalid hex codes are logged and
+    /// leave the theme's color in place.
+    pub fn apply_overrides(&mut self, colors: &ColorConfig) {
+        let overrides = [
+            ("primary", &colors.primary, vec![&mut self.primary]),
+            ("success", &colors.success, vec![&mut self.success, &mut self.gauge_good]),
+            ("warning", &colors.warning, vec![&mut self.warning, &mut self.gauge_warning]),
+            ("danger", &colors.danger, vec![&mut self.error, &mut self.gauge_danger]),
+            ("text", &colors.text, vec![&mut self.foreground]),
+            ("border", &colors.border, vec![&mut self.border]),
+        ];
+        for (name, hex, targets) in overrides {
+            let Some(hex) = hex else {
+                continue;
+            };
+            match parse_hex(hex) {
+                Some(color) => targets.into_iter().for_each(|target| *target = color),
+                None => tracing::warn!("Ignoring ui.colors.{}: '{}' is not a #rrggbb color", name, hex),
+            }
+        }
+    }
+
+    /// The theme's RGB colors, or its ANSI fallback without truecolor
+    pub fn for_terminal(theme: Theme, truecolor: bool) -> Self {
+        if truecolor {
+            Self::from_theme(theme)
+        } else {
+            ansi_fallback(theme)
+        }
+    }
+
     fn default() -> Self {
         Self {
             primary: Color::Cyan,
@@ -102,6 +157,8 @@ impl ThemeColors {
             gauge_good: Color::Green,
             gauge_warning: Color::Yellow,
             gauge_danger: Color::Red,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
         }
     }
 
@@ -121,6 +178,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(80, 250, 123),  // Green
             gauge_warning: Color::Rgb(241, 250, 140), // Yellow
             gauge_danger: Color::Rgb(255, 85, 85),  // Red
+            selection_fg: Color::Rgb(248, 248, 242),  // Foreground
+            selection_bg: Color::Rgb(68, 71, 90),  // Selection
         }
     }
 
@@ -140,6 +199,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(184, 187, 38),  // Green
             gauge_warning: Color::Rgb(250, 189, 47), // Yellow
             gauge_danger: Color::Rgb(251, 73, 52),  // Red
+            selection_fg: Color::Rgb(251, 241, 199),  // Light Yellow
+            selection_bg: Color::Rgb(80, 73, 69),  // Dark Gray
         }
     }
 
@@ -159,6 +220,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(163, 190, 140), // Green
             gauge_warning: Color::Rgb(235, 203, 139), // Yellow
             gauge_danger: Color::Rgb(191, 97, 106), // Red
+            selection_fg: Color::Rgb(236, 239, 244),  // Snow Storm
+            selection_bg: Color::Rgb(67, 76, 94),  // Polar Night
         }
     }
 
@@ -178,6 +241,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(133, 153, 0),    // Green
             gauge_warning: Color::Rgb(181, 137, 0), // Yellow
             gauge_danger: Color::Rgb(220, 50, 47),  // Red
+            selection_fg: Color::Rgb(238, 232, 213),  // Base2
+            selection_bg: Color::Rgb(7, 54, 66),  // Base02
         }
     }
 
@@ -197,6 +262,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(0, 255, 127),    // Spring Green
             gauge_warning: Color::Rgb(255, 255, 0), // Yellow
             gauge_danger: Color::Rgb(255, 0, 71),   // Red
+            selection_fg: Color::Rgb(0, 0, 0),  // Black
+            selection_bg: Color::Rgb(255, 0, 255),  // Magenta
         }
     }
 
@@ -216,6 +283,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(166, 226, 46),   // Green
             gauge_warning: Color::Rgb(255, 255, 0), // Yellow
             gauge_danger: Color::Rgb(249, 38, 114), // Pink
+            selection_fg: Color::Rgb(248, 248, 242),  // Foreground
+            selection_bg: Color::Rgb(73, 72, 62),  // Selection
         }
     }
 
@@ -235,6 +304,8 @@ impl ThemeColors {
             gauge_good: Color::Rgb(152, 195, 121),  // Green
             gauge_warning: Color::Rgb(229, 192, 123), // Yellow
             gauge_danger: Color::Rgb(224, 108, 117), // Red
+            selection_fg: Color::Rgb(220, 223, 228),  // Light Gray
+            selection_bg: Color::Rgb(62, 68, 81),  // Selection
         }
     }
 
@@ -254,6 +325,318 @@ impl ThemeColors {
             gauge_good: Color::Rgb(146, 234, 170),  // Green
             gauge_warning: Color::Rgb(250, 179, 135), // Orange
             gauge_danger: Color::Rgb(242, 139, 130), // Red
+            selection_fg: Color::Rgb(192, 202, 245),  // Foreground
+            selection_bg: Color::Rgb(54, 58, 79),  // Selection
+        }
+    }
+}
+
+/// Hand-picked 16-color version of each theme, for terminals without
+/// truecolor where RGB values would be approximated badly
+pub fn ansi_fallback(theme: Theme) -> ThemeColors {
+    match theme {
+        Theme::Default => ThemeColors::default(),
+        Theme::Dracula => ThemeColors {
+            primary: Color::Magenta,
+            secondary: Color::LightCyan,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            info: Color::LightMagenta,
+            background: Color::Black,
+            foreground: Color::White,
+            text_muted: Color::Blue,
+            highlight: Color::DarkGray,
+            border: Color::Blue,
+            gauge_good: Color::LightGreen,
+            gauge_warning: Color::LightYellow,
+            gauge_danger: Color::LightRed,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+        },
+        Theme::GruvboxDark => ThemeColors {
+            primary: Color::Cyan,
+            secondary: Color::LightYellow,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::LightRed,
+            info: Color::Magenta,
+            background: Color::Black,
+            foreground: Color::White,
+            text_muted: Color::DarkGray,
+            highlight: Color::DarkGray,
+            border: Color::DarkGray,
+            gauge_good: Color::Green,
+            gauge_warning: Color::Yellow,
+            gauge_danger: Color::LightRed,
+            selection_fg: Color::LightYellow,
+            selection_bg: Color::Blue,
+        },
+        Theme::Nord => ThemeColors {
+            primary: Color::LightCyan,
+            secondary: Color::Blue,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            info: Color::Magenta,
+            background: Color::Black,
+            foreground: Color::White,
+            text_muted: Color::DarkGray,
+            highlight: Color::DarkGray,
+            border: Color::DarkGray,
+            gauge_good: Color::Green,
+            gauge_warning: Color::Yellow,
+            gauge_danger: Color::Red,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+        },
+        Theme::SolarizedDark => ThemeColors {
+            primary: Color::Blue,
+            secondary: Color::Cyan,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            info: Color::Magenta,
+            background: Color::Black,
+            foreground: Color::Gray,
+            text_muted: Color::DarkGray,
+            highlight: Color::DarkGray,
+            border: Color::DarkGray,
+            gauge_good: Color::Green,
+            gauge_warning: Color::Yellow,
+            gauge_danger: Color::Red,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+        },
+        Theme::Cyberpunk => ThemeColors {
+            primary: Color::LightCyan,
+            secondary: Color::LightMagenta,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            info: Color::Magenta,
+            background: Color::Black,
+            foreground: Color::LightCyan,
+            text_muted: Color::Gray,
+            highlight: Color::White,
+            border: Color::LightCyan,
+            gauge_good: Color::LightGreen,
+            gauge_warning: Color::LightYellow,
+            gauge_danger: Color::LightRed,
+            selection_fg: Color::Black,
+            selection_bg: Color::LightMagenta,
+        },
+        Theme::Monokai => ThemeColors {
+            primary: Color::LightCyan,
+            secondary: Color::LightMagenta,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightMagenta,
+            info: Color::Magenta,
+            background: Color::Black,
+            foreground: Color::White,
+            text_muted: Color::DarkGray,
+            highlight: Color::DarkGray,
+            border: Color::DarkGray,
+            gauge_good: Color::LightGreen,
+            gauge_warning: Color::LightYellow,
+            gauge_danger: Color::LightMagenta,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+        },
+        Theme::OneDark => ThemeColors {
+            primary: Color::LightBlue,
+            secondary: Color::Magenta,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::LightRed,
+            info: Color::LightMagenta,
+            background: Color::Black,
+            foreground: Color::Gray,
+            text_muted: Color::DarkGray,
+            highlight: Color::DarkGray,
+            border: Color::DarkGray,
+            gauge_good: Color::Green,
+            gauge_warning: Color::Yellow,
+            gauge_danger: Color::LightRed,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+        },
+        Theme::TokyoNight => ThemeColors {
+            primary: Color::LightBlue,
+            secondary: Color::LightMagenta,
+            success: Color::LightGreen,
+            warning: Color::Yellow,
+            error: Color::LightRed,
+            info: Color::Magenta,
+            background: Color::Black,
+            foreground: Color::White,
+            text_muted: Color::DarkGray,
+            highlight: Color::DarkGray,
+            border: Color::DarkGray,
+            gauge_good: Color::LightGreen,
+            gauge_warning: Color::Yellow,
+            gauge_danger: Color::LightRed,
+            selection_fg: Color::White,
+            selection_bg: Color::Blue,
+        },
+    }
+}
+
+/// Whether the terminal advertises 24-bit color through `COLORTERM`
+pub fn supports_truecolor() -> bool {
+    matches!(std::env::var("COLORTERM").as_deref(), Ok("truecolor") | Ok("24bit"))
+}
+
+/// WCAG relative luminance (0 = black, 1 = white) of an RGB color. Palette
+/// colors depend on the terminal's own palette, so they have none.
+pub fn relative_luminance(color: Color) -> Option<f64> {
+    let Color::Rgb(r, g, b) = color else {
+        return None;
+    };
+    let linear = |channel: u8| {
+        let c = channel as f64 / 255.0;
+        if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+    };
+    Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+}
+
+/// WCAG contrast ratio between two RGB colors, from 1:1 to 21:1
+pub fn contrast_ratio(a: Color, b: Color) -> Option<f64> {
+    let (a, b) = (relative_luminance(a)?, relative_luminance(b)?);
+    Some((a.max(b) + 0.05) / (a.min(b) + 0.05))
+}
+
+/// Mix `amount` (0..=1) of `to` into `from`
+fn blend(from: (u8, u8, u8), to: u8, amount: f64) -> Color {
+    let mix = |channel: u8| (channel as f64 + (to as f64 - channel as f64) * amount).round() as u8;
+    Color::Rgb(mix(from.0), mix(from.1), mix(from.2))
+}
+
+impl ThemeColors {
+    /// Check the text colors against the background they're drawn on. Each
+    /// pair below `min_ratio` is returned as e.g. "text_muted 2.9:1"; with
+    /// `adjust` its foreground is first moved toward white (dark backgrounds)
+    /// or black until it passes, or as far as it can go.
+    pub fn enforce_contrast(&mut self, min_ratio: f64, adjust: bool) -> Vec<String> {
+        let background = self.background;
+        let selection_bg = self.selection_bg;
+        let pairs = [
+            ("foreground", &mut self.foreground, background),
+            ("text_muted", &mut self.text_muted, background),
+            ("primary", &mut self.primary, background),
+            ("success", &mut self.success, background),
+            ("warning", &mut self.warning, background),
+            ("error", &mut self.error, background),
+            ("info", &mut self.info, background),
+            ("selection_fg", &mut self.selection_fg, selection_bg),
+        ];
+
+        let mut low = Vec::new();
+        for (name, foreground, background) in pairs {
+            let Some(ratio) = contrast_ratio(*foreground, background) else {
+                continue;
+            };
+            if ratio >= min_ratio {
+                continue;
+            }
+            low.push(format!("{} {:.1}:1", name, ratio));
+            if let (true, Color::Rgb(r, g, b)) = (adjust, *foreground) {
+                let toward = if relative_luminance(background).unwrap_or(0.0) < 0.18 { 255 } else { 0 };
+                for step in 1..=10 {
+                    *foreground = blend((r, g, b), toward, step as f64 / 10.0);
+                    if contrast_ratio(*foreground, background).is_some_and(|ratio| ratio >= min_ratio) {
+                        break;
+                    }
+                }
+            }
+        }
+        low
+    }
+}
+
+/// `#rrggbb` (the `#` is optional) as an RGB color
+fn parse_hex(hex: &str) -> Option<Color> {
+    let digits = hex.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_overrides_replace_valid_hex_only() {
+        let mut colors = ThemeColors::from_theme(Theme::Nord);
+        let border = colors.border;
+        colors.apply_overrides(&ColorConfig {
+            primary: Some("#bd93f9".to_string()),
+            danger: Some("ff5555".to_string()),
+            border: Some("#12345".to_string()),
+            ..ColorConfig::default()
+        });
+        assert_eq!(colors.primary, Color::Rgb(0xbd, 0x93, 0xf9));
+        assert_eq!(colors.gauge_danger, Color::Rgb(0xff, 0x55, 0x55));
+        assert_eq!(colors.border, border);
+    }
+
+    /// A named color as xterm's default palette draws it, to check the ANSI
+    /// fallbacks against a typical terminal
+    fn xterm_rgb(color: Color) -> Color {
+        match color {
+            Color::Black => Color::Rgb(0, 0, 0),
+            Color::Red => Color::Rgb(205, 0, 0),
+            Color::Green => Color::Rgb(0, 205, 0),
+            Color::Yellow => Color::Rgb(205, 205, 0),
+            Color::Blue => Color::Rgb(0, 0, 238),
+            Color::Magenta => Color::Rgb(205, 0, 205),
+            Color::Cyan => Color::Rgb(0, 205, 205),
+            Color::Gray => Color::Rgb(229, 229, 229),
+            Color::DarkGray => Color::Rgb(127, 127, 127),
+            Color::LightRed => Color::Rgb(255, 0, 0),
+            Color::LightGreen => Color::Rgb(0, 255, 0),
+            Color::LightYellow => Color::Rgb(255, 255, 0),
+            Color::LightBlue => Color::Rgb(92, 92, 255),
+            Color::LightMagenta => Color::Rgb(255, 0, 255),
+            Color::LightCyan => Color::Rgb(0, 255, 255),
+            Color::White => Color::Rgb(255, 255, 255),
+            color => color,
+        }
+    }
+
+    #[test]
+    fn selection_colors_are_readable_in_every_theme() {
+        for theme in Theme::ALL {
+            for (palette, colors) in [("rgb", ThemeColors::from_theme(theme)), ("ansi", ansi_fallback(theme))] {
+                let ratio = contrast_ratio(xterm_rgb(colors.selection_fg), xterm_rgb(colors.selection_bg)).unwrap();
+                assert!(ratio >= 4.5, "{:?} {} selection contrast {:.1}:1", theme, palette, ratio);
+            }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn contrast_ratio_matches_wcag_extremes() {
+        let black = Color::Rgb(0, 0, 0);
+        let white = Color::Rgb(255, 255, 255);
+        assert!((contrast_ratio(black, white).unwrap() - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(white, white).unwrap() - 1.0).abs() < 0.01);
+        assert_eq!(contrast_ratio(Color::Black, white), None);
+    }
+
+    #[test]
+    fn low_contrast_foreground_is_reported_and_adjusted() {
+        let mut colors = ThemeColors::from_theme(Theme::Dracula);
+        colors.background = Color::Rgb(0x28, 0x2a, 0x36);
+        colors.foreground = Color::Rgb(0x30, 0x32, 0x40);
+
+        let low = colors.clone().enforce_contrast(4.5, false);
+        assert!(low.iter().any(|entry| entry.starts_with("foreground ")));
+
+        colors.enforce_contrast(4.5, true);
+        assert!(contrast_ratio(colors.foreground, colors.background).unwrap() >= 4.5);
+    }
+}
@@ -1,14 +1,22 @@
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
 use ratatui::{
+    buffer::Buffer,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style, Stylize},
+    symbols,
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Gauge, List, ListItem, Paragraph, Sparkline, Table, Row,
+        Axis, Block, BorderType, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, Paragraph, Sparkline, Table, Tabs, Row, Wrap,
     },
     Frame,
 };
 
-use crate::app::{App, ActivePanel};
+use crate::alerts::AlertState;
+use crate::app::{App, ActivePanel, DisplayMode, MetricAvailability, NodeRow, NodeSortKey, ServiceSortKey, UnifiedItem};
+use crate::config::{CurrentTab, GraphStyle, NodeLayout, SelectionStyle};
+use crate::mock_data::NodeMetrics;
+use crate::format::{convert_temperature, format_network_rate, scale_network_rate};
 
 pub fn ui(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -16,70 +24,506 @@ pub fn ui(f: &mut Frame, app: &App) {
         .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)].as_ref())
         .split(f.area());
 
+    // Tabs without a table leave these empty, so the mouse can't hit a table from an earlier frame
+    app.nodes_table_area.set(Rect::default());
+    app.services_table_area.set(Rect::default());
+
     render_title_bar(f, app, chunks[0]);
-    render_main_content(f, app, chunks[1]);
+    if !app.critical_temps.is_empty() {
+        // Thermal runaway takes the place of the alerts panel
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+            .split(chunks[1]);
+        render_critical_temp_banner(f, app, main_chunks[0]);
+        render_main_content(f, app, main_chunks[1]);
+    } else if app.alert_engine.has_rules() {
+        // Alerts panel grows with the number of pending/firing alerts
+        let active = app.alert_engine.alerts.iter().filter(|alert| alert.state != AlertState::Ok).count();
+        let main_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(active.clamp(1, 5) as u16 + 2)].as_ref())
+            .split(chunks[1]);
+        render_main_content(f, app, main_chunks[0]);
+        render_alerts(f, app, main_chunks[1]);
+    } else {
+        render_main_content(f, app, chunks[1]);
+    }
     render_status_bar(f, app, chunks[2]);
+
+    if app.show_legend {
+        render_legend(f, app, chunks[1]);
+    }
+    if app.no_data() {
+        render_no_data_banner(f, app, chunks[1]);
+    }
+    if app.show_node_detail {
+        render_node_detail_popup(f, app, chunks[1]);
+    }
+    if app.show_diagnostics {
+        render_diagnostics(f, app, chunks[1]);
+    }
+    if app.scratchpad.open {
+        render_scratchpad(f, app, chunks[1]);
+    }
+    if app.show_help {
+        render_help_popup(f, app, chunks[1]);
+    }
+    if app.visual_bell_active() {
+        // Visual bell: invert the whole screen for one frame
+        let area = f.area();
+        f.buffer_mut().set_style(area, Style::default().add_modifier(Modifier::REVERSED));
+    }
 }
 
-fn render_title_bar(f: &mut Frame, app: &App, area: Rect) {
-    let title_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(20), Constraint::Percentage(40), Constraint::Min(20)].as_ref())
-        .split(area);
+/// Every key binding, for the '?' overlay
+const KEY_BINDINGS: &[(&str, &str)] = &[
+    ("q", "Quit"),
+    ("? / F1", "Show/hide this help"),
+    ("Tab / Shift+Tab", "Next/previous tab"),
+    ("1-9", "Jump to a tab"),
+    ("w / h / l", "Switch between nodes and services"),
+    ("↑↓ / k j", "Move the selection"),
+    ("← / →", "Previous service / next node"),
+    ("Enter", "Node details popup"),
+    ("Esc", "Close the node popup"),
+    ("Space", "Add/remove from Compare"),
+    ("r", "Toggle filter"),
+    ("s / S", "Sort column / direction of the active table"),
+    ("R", "Refresh from Prometheus now"),
+    ("p", "Pause/resume updates"),
+    ("t / T", "Next/previous theme"),
+    ("%", "Percent or absolute values"),
+    ("L", "Threshold legend"),
+    ("v", "Show query names in titles"),
+    ("/", "Filter services by name"),
+    ("u", "Unified list (/ to search)"),
+    ("P", "Pin the detail panels"),
+    ("+ / -", "Zoom the graphs out/in"),
+    ("D", "Query diagnostics"),
+    (":", "PromQL scratchpad"),
+    ("n", "Note for the service"),
+    ("b / B", "Save / compare baseline"),
+    ("H", "HTML snapshot"),
+    ("Ctrl+S", "Screen as text"),
+    ("Ctrl+D", "State dump"),
+    ("Ctrl+R", "Reset the view"),
+];
+
+/// Key binding reference ('?'), in two columns
+fn render_help_popup(f: &mut Frame, app: &App, area: Rect) {
+    let half = KEY_BINDINGS.len().div_ceil(2);
+    let width = 100.min(area.width);
+    let height = (half as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let key_style = Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD);
+    let action_style = Style::default().fg(app.theme_colors.foreground);
+    let cells = |index: usize| {
+        KEY_BINDINGS.get(index).map_or([Cell::from(""), Cell::from("")], |(key, action)| {
+            [Cell::from(*key).style(key_style), Cell::from(*action).style(action_style)]
+        })
+    };
+    let rows = (0..half).map(|i| Row::new(cells(i).into_iter().chain(cells(i + half))));
+    let columns = [Constraint::Length(15), Constraint::Fill(1), Constraint::Length(15), Constraint::Fill(1)];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Keys (? or Esc to close)")
+        .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(app.theme_colors.info));
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Table::new(rows, columns).block(block), popup_area);
+}
+
+/// PromQL console (':'): the input line, then the last result, or recent
+/// queries before the first one runs
+fn render_scratchpad(f: &mut Frame, app: &App, area: Rect) {
+    let scratchpad = &app.scratchpad;
+    let width = (area.width * 9 / 10).max(40.min(area.width));
+    let height = (area.height * 4 / 5).max(6.min(area.height));
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let muted = Style::default().fg(app.theme_colors.text_muted);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(":", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("{}▏", scratchpad.input), Style::default().fg(app.theme_colors.foreground)),
+        ]),
+        Line::from(""),
+    ];
+    if let Some(query) = scratchpad.running() {
+        lines.push(Line::from(Span::styled(format!("Running {}…", query), muted)));
+    } else if let Some((query, result)) = &scratchpad.result {
+        match result {
+            Ok(series) => {
+                lines.push(Line::from(Span::styled(format!("{} · {} series", query, series.len()), muted)));
+                lines.extend(series.iter().map(|line| Line::from(Span::styled(line.as_str(), Style::default().fg(app.theme_colors.foreground)))));
+            }
+            Err(e) => {
+                lines.push(Line::from(Span::styled(query.as_str(), muted)));
+                lines.push(Line::from(Span::styled(e.as_str(), Style::default().fg(app.theme_colors.error))));
+            }
+        }
+    } else {
+        lines.push(Line::from(Span::styled("Recent queries (↑/↓):", muted)));
+        lines.extend(scratchpad.history().map(|query| Line::from(Span::styled(query.as_str(), muted))));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("PromQL Scratchpad (Enter run · ↑/↓ history · Esc close)")
+        .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(app.theme_colors.info));
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), popup_area);
+}
+
+/// Every metric and hardware spec of the selected node, including the ones
+/// the table has no room for
+fn render_node_detail_popup(f: &mut Frame, app: &App, area: Rect) {
+    let Some((name, node)) = app.selected_node_name().and_then(|name| app.nodes.get_key_value(&name)) else {
+        return;
+    };
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    let metric = |metric: &str, text: String| match app.node_metric_availability(node, metric) {
+        MetricAvailability::NotApplicable => "—".to_string(),
+        _ => localized(app, text),
+    };
+    let fields = [
+        ("Address", node.ip_address.clone()),
+        ("Status", node.status.clone()),
+        ("Uptime", crate::format::format_uptime(node.uptime)),
+        ("CPU", format!("{} ({} cores / {} threads)", node.cpu_model, node.cpu_cores, node.cpu_threads)),
+        ("CPU usage", metric("cpu", format!("{:.1}%", node.cpu_usage))),
+        ("Load", metric("load", format!("{:.2} {:.2} {:.2}", node.load_avg[0], node.load_avg[1], node.load_avg[2]))),
+        ("Memory", metric("memory", format!("{:.1}% of {:.1}GB", node.memory_usage, node.memory_total as f64 / GB))),
+        ("Swap", metric("swap", format!("{:.1}%", node.swap_usage))),
+        ("GPU", metric("gpu", node.gpu_model.clone())),
+        ("GPU usage", metric("gpu", format!("{:.1}%", node.gpu_usage))),
+        ("VRAM", metric("gpu", format!("{:.1}% of {:.1}GB", node.gpu_memory, node.gpu_memory_total as f64 / GB))),
+        ("Disk", metric("disk", format!("{:.1}% of {:.0}GB", node.disk_usage, node.disk_total_gb))),
+        ("Temperature", metric("temperature", format_node_temperature(app, name, node.temperature))),
+        ("Network", metric("network", format_node_network(app, name, node))),
+        ("Net errors", metric("network_errors", format!("{:.2}/s", node.network_errors))),
+        ("Net drops", metric("network_drops", format!("{:.2}/s", node.network_drops))),
+    ];
+
+    let width = 60.min(area.width);
+    let height = (fields.len() as u16 + 2).min(area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let lines: Vec<Line> = fields
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(format!("{:<13}", label), Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
+                Span::styled(value, Style::default().fg(app.theme_colors.foreground)),
+            ])
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Node {} (Esc to close)", node.name))
+        .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(app.theme_colors.info));
+    f.render_widget(Clear, popup_area);
+    f.render_widget(Paragraph::new(lines).block(block), popup_area);
+}
 
-    let title = Paragraph::new("Monitorium")
-        .style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+/// Banner over the main area while every query fails (`on_total_failure: blank`)
+fn render_no_data_banner(f: &mut Frame, app: &App, area: Rect) {
+    let width = 40.min(area.width);
+    let height = 5.min(area.height);
+    let banner_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let banner = Paragraph::new(vec![
+        Line::from(Span::styled("NO DATA", Style::default().fg(app.theme_colors.error).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        Line::from(Span::styled("every Prometheus query is failing", Style::default().fg(app.theme_colors.text_muted))),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(app.theme_colors.error)),
+    );
+    f.render_widget(Clear, banner_area);
+    f.render_widget(banner, banner_area);
+}
+
+/// Popup table of every query's outcomes since launch, most failures first
+fn render_diagnostics(f: &mut Frame, app: &App, area: Rect) {
+    let stats = &app.query_stats;
+    let width = (area.width * 9 / 10).max(40.min(area.width));
+    let height = (stats.len() as u16 + 3).clamp(5, area.height);
+    let popup_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Query Diagnostics (D to close)")
+        .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(app.theme_colors.info));
+    f.render_widget(Clear, popup_area);
+
+    if stats.is_empty() {
+        let message = if app.prometheus_client.is_some() || app.prometheus_update_in_flight() {
+            "No queries sent yet"
+        } else {
+            "No Prometheus client (mock data)"
+        };
+        let paragraph = Paragraph::new(Span::styled(message, Style::default().fg(app.theme_colors.text_muted))).block(block);
+        f.render_widget(paragraph, popup_area);
+        return;
+    }
+
+    let header = Row::new(["Query", "Status", "Latency", "OK", "Failed", "Last error"].map(|h| {
+        Cell::from(h).style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+    }))
+    .style(Style::default().bg(app.theme_colors.border));
+    let rows = stats.iter().map(|(query, stats)| {
+        let (status, color) = if stats.last_ok { ("ok", app.theme_colors.success) } else { ("error", app.theme_colors.error) };
+        Row::new(vec![
+            Cell::from(query.as_str()),
+            Cell::from(status).style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Cell::from(format!("{}ms", stats.last_latency.as_millis())),
+            Cell::from(stats.successes.to_string()),
+            Cell::from(stats.failures.to_string())
+                .style(Style::default().fg(if stats.failures > 0 { app.theme_colors.error } else { app.theme_colors.foreground })),
+            Cell::from(stats.last_error.as_deref().unwrap_or("")).style(Style::default().fg(app.theme_colors.text_muted)),
+        ])
+    });
+    let columns = [
+        Constraint::Fill(2),
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Length(6),
+        Constraint::Length(6),
+        Constraint::Fill(1),
+    ];
+    f.render_widget(Table::new(rows, columns).header(header).block(block), popup_area);
+}
+
+/// Full-width red banner naming every node at or above `alerts.temp_critical`,
+/// each with the limit in that node's temperature unit
+fn render_critical_temp_banner(f: &mut Frame, app: &App, area: Rect) {
+    let limit = app.config.alerts.temp_critical.unwrap_or_default();
+    let mut spans = vec![Span::styled(
+        "🔥 CRITICAL TEMPERATURE: ",
+        Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+    )];
+    for (index, (name, temperature)) in app.critical_temps.iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(" · ", Style::default().fg(Color::White)));
+        }
+        spans.push(Span::styled(
+            format!("{} {} (≥{})", name, format_node_temperature(app, name, *temperature), format_node_temperature(app, name, limit)),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let banner = Paragraph::new(Line::from(spans))
+        .alignment(Alignment::Center)
+        .style(Style::default().bg(app.theme_colors.error))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(app.theme_colors.border))
-                .title("Homelab Monitoring")
-                .title_style(Style::default().fg(app.theme_colors.secondary).add_modifier(Modifier::BOLD))
-                .title_alignment(Alignment::Center),
+                .border_type(BorderType::Thick)
+                .border_style(Style::default().fg(Color::White).bg(app.theme_colors.error)),
         );
+    f.render_widget(banner, area);
+}
 
-    f.render_widget(title, title_chunks[0]);
+fn render_alerts(f: &mut Frame, app: &App, area: Rect) {
+    let alerts = &app.alert_engine.alerts;
+    let active: Vec<_> = alerts.iter().filter(|alert| alert.state != AlertState::Ok).collect();
+
+    let lines: Vec<Line> = if active.is_empty() {
+        vec![Line::from(Span::styled(
+            format!("✓ all {} checks OK", alerts.len()),
+            Style::default().fg(app.theme_colors.success),
+        ))]
+    } else {
+        active
+            .iter()
+            .map(|alert| {
+                let (label, color) = match alert.state {
+                    AlertState::Firing => ("FIRING ", app.theme_colors.error),
+                    _ => ("PENDING", app.theme_colors.warning),
+                };
+                Line::from(vec![
+                    Span::styled(label, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" {} on {} ({:.1})", alert.rule, alert.target, alert.value)),
+                ])
+            })
+            .collect()
+    };
 
-    // Show current selections with active panel indicator
-    let panel_indicator = match app.active_panel {
-        crate::app::ActivePanel::Nodes => "▼",
-        crate::app::ActivePanel::Services => "▼",
+    let firing = active.iter().filter(|alert| alert.state == AlertState::Firing).count();
+    let panel = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Alerts ({} firing, {} pending)", firing, active.len() - firing))
+                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(if firing > 0 { app.theme_colors.error } else { app.theme_colors.border })),
+        );
+    f.render_widget(panel, area);
+}
+
+/// Color swatches for the usage thresholds, in the bottom-right corner of `area`
+fn render_legend(f: &mut Frame, app: &App, area: Rect) {
+    let thresholds = &app.config.ui.thresholds;
+    let swatch = |color: Color, label: String| {
+        vec![Span::styled("■ ", Style::default().fg(color)), Span::raw(label), Span::raw("  ")]
     };
+    let spans: Vec<Span> = [
+        swatch(app.theme_colors.gauge_good, format!("≤{:.0}%", thresholds.warning)),
+        swatch(app.theme_colors.gauge_warning, format!("{:.0}–{:.0}%", thresholds.warning, thresholds.critical)),
+        swatch(app.theme_colors.gauge_danger, format!(">{:.0}%", thresholds.critical)),
+        swatch(app.theme_colors.text_muted, "stale".to_string()),
+    ]
+    .concat();
+    let lines = vec![
+        Line::from(spans),
+        Line::styled("⌂ this machine · ⚠ swap in use · — not on this node · GPU sort: — (no GPU) nodes always last", Style::default().fg(app.theme_colors.text_muted)),
+    ];
 
-    let selected_node = app.nodes.keys().nth(app.selected_node_index).map_or("None", |v| v);
-    let selected_service = app.services.keys().nth(app.selected_service_index).map_or("None", |v| v);
+    let content_width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+    let width = (content_width + 2).min(area.width);
+    let legend_area = Rect {
+        x: area.right().saturating_sub(width),
+        y: area.bottom().saturating_sub(4),
+        width,
+        height: 4.min(area.height),
+    };
 
-    let selected_info = format!("{} Node: {} | Service: {}",
-        panel_indicator,
-        selected_node,
-        selected_service
-    );
+    let legend = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Legend")
+                .title_style(Style::default().fg(app.theme_colors.primary))
+                .border_style(Style::default().fg(app.theme_colors.border)),
+        );
+    f.render_widget(Clear, legend_area);
+    f.render_widget(legend, legend_area);
+}
 
-    let info = Paragraph::new(selected_info)
-        .style(Style::default().fg(app.theme_colors.foreground))
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme_colors.border)))
-        .alignment(Alignment::Center);
+fn render_title_bar(f: &mut Frame, app: &App, area: Rect) {
+    let title_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(20), Constraint::Percentage(40), Constraint::Min(20)].as_ref())
+        .split(area);
+
+    // Weighted service health colors the border, so a critical failure shows on every tab
+    let health = app.weighted_health_score();
+    let health_color = match health {
+        Some(score) if score >= 0.9 => app.theme_colors.success,
+        Some(score) if score >= 0.6 => app.theme_colors.gauge_warning,
+        Some(_) => app.theme_colors.gauge_danger,
+        None => app.theme_colors.border,
+    };
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(health_color))
+        .title(app.config.general.cluster_name.as_str())
+        .title_style(Style::default().fg(app.theme_colors.secondary).add_modifier(Modifier::BOLD))
+        .title_alignment(Alignment::Center);
+    if let Some(score) = health {
+        block = block.title_bottom(
+            Line::styled(format!(" health {:.0}% ", score * 100.0), Style::default().fg(health_color).add_modifier(Modifier::BOLD))
+                .right_aligned(),
+        );
+    }
+    let title = Paragraph::new(Line::from(vec![
+        Span::styled("Monitorium", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" @ {}", app.config.prometheus.host()), Style::default().fg(app.theme_colors.text_muted)),
+    ]))
+        .block(block);
 
-    f.render_widget(info, title_chunks[1]);
+    f.render_widget(title, title_chunks[0]);
 
-    // Show active panel on the right
-    let active_text = format!("Panel: {}", match app.active_panel {
-        crate::app::ActivePanel::Nodes => "Nodes",
-        crate::app::ActivePanel::Services => "Services",
-    });
+    // Tab bar, numbered for the 1-9 shortcuts
+    let titles: Vec<String> = app.config.ui.tabs
+        .iter()
+        .enumerate()
+        .map(|(index, tab)| format!("{} {}", index + 1, tab.label()))
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(app.tab_index())
+        .style(Style::default().fg(app.theme_colors.text_muted))
+        .highlight_style(Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD | Modifier::REVERSED))
+        .divider(Span::styled("│", Style::default().fg(app.theme_colors.border)))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme_colors.border)));
+
+    f.render_widget(tabs, title_chunks[1]);
+
+    // Current selections, with the focused table's one highlighted
+    let selection_style = |panel: ActivePanel| {
+        if app.active_panel == panel {
+            Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme_colors.foreground)
+        }
+    };
+    let selected_info = Line::from(vec![
+        Span::styled(
+            format!("Node: {}", app.selected_node_name().unwrap_or_else(|| "None".to_string())),
+            selection_style(ActivePanel::Nodes),
+        ),
+        Span::styled(" | ", Style::default().fg(app.theme_colors.text_muted)),
+        Span::styled(
+            format!("Service: {}", app.selected_service_name().unwrap_or_else(|| "None".to_string())),
+            selection_style(ActivePanel::Services),
+        ),
+    ]);
 
-    let active_panel = Paragraph::new(active_text)
-        .style(Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD))
+    let info = Paragraph::new(selected_info)
         .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme_colors.border)))
         .alignment(Alignment::Center);
 
-    f.render_widget(active_panel, title_chunks[2]);
+    f.render_widget(info, title_chunks[2]);
 }
 
 fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let connection_label = match (&app.connection_status, app.retry_in()) {
+        (crate::app::ConnectionStatus::Connecting, Some(retry_in)) => format!(
+            "reconnecting (attempt {}, retry in {}s)",
+            app.reconnect_attempt,
+            retry_in.as_secs_f64().ceil() as u64
+        ),
+        _ => "Prometheus".to_string(),
+    };
+
+    // Leave room for the reconnect countdown
+    let status_width = if app.retry_in().is_some() { 85 } else { 55 }
+        + if app.battery_throttled() { 12 } else { 0 };
     let status_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(0), Constraint::Length(55)].as_ref())
+        .constraints([Constraint::Min(0), Constraint::Length(status_width)].as_ref())
         .split(area);
 
     let help_text = vec![
@@ -87,16 +531,42 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
             Span::styled("q", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
             Span::raw(":quit "),
             Span::styled("Tab", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
+            Span::raw(":tab "),
+            Span::styled("w/hl", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
             Span::raw(":panel "),
-            Span::styled("↑↓", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
+            Span::styled("↑↓/jk", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
             Span::raw(":navigate "),
             Span::styled("t", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
-            Span::raw(":theme"),
+            Span::raw(":theme "),
+            Span::styled("%", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
+            Span::raw(":units "),
+            Span::styled("s", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
+            Span::raw(":sort "),
+            Span::styled("L", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
+            Span::raw(":legend "),
+            Span::styled("?", Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)),
+            Span::raw(":help"),
         ]),
     ];
 
+    let help_text = match (&app.service_filter_input, app.current_status_message()) {
+        (Some(text), _) => vec![Line::from(vec![
+            Span::styled("Filter services: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
+            Span::raw(format!("/{}▏", text)),
+            Span::styled("  Enter:apply Esc:cancel", Style::default().fg(app.theme_colors.text_muted)),
+        ])],
+        (None, Some(message)) => vec![Line::from(Span::styled(message, Style::default().fg(app.theme_colors.info)))],
+        (None, None) => help_text,
+    };
+
     let help = Paragraph::new(help_text)
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme_colors.border)));
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} ", app.view_breadcrumb()))
+                .title_style(Style::default().fg(app.theme_colors.text_muted))
+                .border_style(Style::default().fg(app.theme_colors.border)),
+        );
     f.render_widget(help, status_chunks[0]);
 
     let connection_indicator = match app.connection_status {
@@ -106,21 +576,245 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
     };
 
     let status_text = vec![Line::from(vec![
-        Span::raw(format!("{} Prometheus | Tick: {} | Theme: {}",
+        Span::raw(format!("{} {} | Tick: {} | Theme: {}",
             connection_indicator,
+            connection_label,
             app.tick_count,
             app.current_theme.name()
-        ))
+        )),
+        Span::styled(if app.battery_throttled() { " | 🔋 saver" } else { "" }, Style::default().fg(app.theme_colors.warning)),
+        Span::styled(if app.manual_refresh { " | Refreshing..." } else { "" }, Style::default().fg(app.theme_colors.info)),
+        Span::styled(if app.paused { " | ⏸ PAUSED" } else { "" }, Style::default().fg(app.theme_colors.warning).add_modifier(Modifier::BOLD)),
     ])];
 
+    let mut status_block = Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme_colors.border));
+    let reason = match &app.connection_status {
+        crate::app::ConnectionStatus::Disconnected(reason) => Some(reason),
+        crate::app::ConnectionStatus::Connecting => app.recent_errors.last(),
+        crate::app::ConnectionStatus::Connected => None,
+    };
+    if let Some(reason) = reason {
+        // Show why we're on mock data, trimmed to fit the top border
+        let max_len = status_chunks[1].width.saturating_sub(4) as usize;
+        let reason: String = reason.chars().take(max_len).collect();
+        status_block = status_block
+            .title(reason)
+            .title_style(Style::default().fg(app.theme_colors.error));
+    }
+
     let status = Paragraph::new(status_text)
         .style(Style::default().fg(app.theme_colors.text_muted))
-        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.theme_colors.border)))
+        .block(status_block)
         .alignment(Alignment::Right);
     f.render_widget(status, status_chunks[1]);
 }
 
+/// Number of data rows that fit in a bordered table with a one-line header
+fn table_capacity(area: Rect) -> usize {
+    area.height.saturating_sub(3) as usize
+}
+
+/// Range of rows to draw, scrolled just enough to keep the selected row in view
+fn visible_rows(total: usize, capacity: usize, selected: usize) -> (usize, usize) {
+    if total <= capacity {
+        return (0, total);
+    }
+    let start = (selected + 1).saturating_sub(capacity).min(total - capacity);
+    (start, start + capacity)
+}
+
+/// " · showing 1–12 of 40" title suffix when not every row fits
+fn pagination_label(start: usize, end: usize, total: usize) -> String {
+    if end - start >= total {
+        String::new()
+    } else {
+        format!(" · showing {}–{} of {}", start + 1, end, total)
+    }
+}
+
+/// A formatted metric value with the separators of `ui.locale`
+fn localized(app: &App, text: String) -> String {
+    crate::format::localize(&text, app.config.ui.locale)
+}
+
+/// A Celsius reading in the node's configured temperature unit
+fn format_node_temperature(app: &App, node_name: &str, celsius: f64) -> String {
+    let (temperature, symbol) = convert_temperature(celsius, app.node_temperature_unit(node_name));
+    localized(app, format!("{:.1}{}", temperature, symbol))
+}
+
+/// Receive and transmit rates in the node's configured network unit
+fn format_node_network(app: &App, node_name: &str, node: &NodeMetrics) -> String {
+    let unit = app.node_network_unit(node_name);
+    let (rx, unit_label) = scale_network_rate(node.network_rx, unit);
+    let (tx, _) = scale_network_rate(node.network_tx, unit);
+    localized(app, format!("↓{} ↑{}{}", rx, tx, unit_label))
+}
+
+/// Node CPU as a percentage or as busy threads out of the total.
+/// Thresholds always use the percentage; only the text changes.
+fn format_node_cpu(app: &App, node: &NodeMetrics) -> String {
+    localized(app, match app.display_mode {
+        DisplayMode::Percent => format!("{:.1}%", node.cpu_usage),
+        DisplayMode::Absolute => format!("{:.1}/{} thr", node.cpu_usage / 100.0 * node.cpu_threads as f64, node.cpu_threads),
+    })
+}
+
+/// Node memory as a percentage or as used/total GB
+fn format_node_memory(app: &App, node: &NodeMetrics) -> String {
+    localized(app, match app.display_mode {
+        DisplayMode::Percent => format!("{:.1}%", node.memory_usage),
+        DisplayMode::Absolute => format!("{:.1}/{:.0}GB", node.memory_usage / 100.0 * node.memory_total_gb, node.memory_total_gb),
+    })
+}
+
+/// Node disk as a percentage or as used/total GB
+fn format_node_disk(app: &App, node: &NodeMetrics) -> String {
+    localized(app, match app.display_mode {
+        DisplayMode::Percent => format!("{:.1}%", node.disk_usage),
+        DisplayMode::Absolute => format!("{:.0}/{:.0}GB", node.disk_usage / 100.0 * node.disk_total_gb, node.disk_total_gb),
+    })
+}
+
+/// Column widths for a bordered table whose first (name) column absorbs spare
+/// width. Trailing columns are dropped when the table is too narrow for all of them.
+fn responsive_columns(width: u16, name_min: u16, fixed: &[u16]) -> Vec<Constraint> {
+    let inner_width = width.saturating_sub(2);
+    let mut count = fixed.len();
+    // Each column after the name one also takes a single space of spacing
+    while count > 0 && name_min + fixed[..count].iter().map(|w| w + 1).sum::<u16>() > inner_width {
+        count -= 1;
+    }
+
+    std::iter::once(Constraint::Fill(1))
+        .chain(fixed[..count].iter().map(|&w| Constraint::Length(w)))
+        .collect()
+}
+
+/// Table row with the configured selection style, or underlined while hovered
+fn styled_row<'a>(app: &App, cells: Vec<Cell<'a>>, selected: bool, hovered: bool) -> Row<'a> {
+    if !selected {
+        let style = if hovered { Style::default().add_modifier(Modifier::UNDERLINED) } else { Style::default() };
+        return Row::new(cells).style(style);
+    }
+
+    match app.config.ui.selection_style {
+        SelectionStyle::Theme => {
+            // Per-cell metric colors would clash with the selection background,
+            // so the whole row uses the theme's selection pair
+            let style = Style::default()
+                .fg(app.theme_colors.selection_fg)
+                .bg(app.theme_colors.selection_bg)
+                .add_modifier(Modifier::BOLD);
+            Row::new(cells.into_iter().map(|cell| cell.style(style))).style(style)
+        }
+        SelectionStyle::Reverse => Row::new(cells).style(Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD)),
+        SelectionStyle::Underline => Row::new(cells).style(Style::default().add_modifier(Modifier::UNDERLINED | Modifier::BOLD)),
+    }
+}
+
+/// Panel title with the source metric appended when queries are shown,
+/// e.g. "CPU Usage [node_cpu_seconds_total]"
+fn with_query(app: &App, title: impl Into<String>, query: &str) -> String {
+    let title = title.into();
+    match promql_metric_name(query) {
+        Some(metric) if app.show_queries => format!("{} [{}]", title, metric),
+        _ => title,
+    }
+}
+
+/// First metric name in a PromQL expression, skipping functions, label
+/// matchers, range selectors and grouping clauses
+fn promql_metric_name(query: &str) -> Option<&str> {
+    const GROUPING: [&str; 6] = ["by", "without", "on", "ignoring", "group_left", "group_right"];
+    // Keywords and aggregations, which may be followed by `by (...)` rather than `(`
+    const KEYWORDS: [&str; 17] = [
+        "bool", "and", "or", "unless", "offset", "sum", "min", "max", "avg", "group", "stddev",
+        "stdvar", "count", "count_values", "bottomk", "topk", "quantile",
+    ];
+
+    let bytes = query.as_bytes();
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b':';
+    let skip_past = |from: usize, close: u8| {
+        bytes[from..].iter().position(|&b| b == close).map_or(bytes.len(), |offset| from + offset + 1)
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'{' {
+            i = skip_past(i, b'}');
+        } else if b == b'[' {
+            i = skip_past(i, b']');
+        } else if b == b'"' {
+            i = skip_past(i + 1, b'"');
+        } else if b.is_ascii_digit() {
+            // Numbers, including exponents like 1e3
+            while i < bytes.len() && (is_ident(bytes[i]) || bytes[i] == b'.') {
+                i += 1;
+            }
+        } else if is_ident(b) {
+            let start = i;
+            while i < bytes.len() && is_ident(bytes[i]) {
+                i += 1;
+            }
+            let ident = &query[start..i];
+            let rest = query[i..].trim_start();
+
+            if GROUPING.contains(&ident) {
+                if rest.starts_with('(') {
+                    i = skip_past(i, b')');
+                }
+            } else if !rest.starts_with('(') && !KEYWORDS.contains(&ident) {
+                return Some(ident);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Gauge color for a percentage against the configured thresholds
+fn usage_color(app: &App, percent: f64) -> Color {
+    let thresholds = &app.config.ui.thresholds;
+    if percent > thresholds.critical { app.theme_colors.gauge_danger }
+    else if percent > thresholds.warning { app.theme_colors.gauge_warning }
+    else { app.theme_colors.gauge_good }
+}
+
+/// Style for a metric cell, greyed out when the value is stale
+fn metric_style(app: &App, stale: bool, color: Color) -> Style {
+    if stale || app.data_frozen() {
+        Style::default().fg(app.theme_colors.text_muted).add_modifier(Modifier::DIM)
+    } else {
+        Style::default().fg(color)
+    }
+}
+
+/// A node table cell: "—" when the metric doesn't apply to the node, dimmed
+/// when it stopped reporting
+fn node_metric_cell<'a>(app: &App, node: &NodeMetrics, metric: &str, text: String, color: Color) -> Cell<'a> {
+    match app.node_metric_availability(node, metric) {
+        MetricAvailability::NotApplicable => Cell::from("—").style(Style::default().fg(app.theme_colors.text_muted)),
+        availability => Cell::from(text).style(metric_style(app, availability == MetricAvailability::Failed, color)),
+    }
+}
+
 fn render_main_content(f: &mut Frame, app: &App, area: Rect) {
+    if app.unified_view {
+        render_unified_list(f, app, area);
+        return;
+    }
+    match app.current_tab {
+        CurrentTab::Overview => render_overview(f, app, area),
+        CurrentTab::Nodes => render_nodes_panel(f, app, area),
+        CurrentTab::Services => render_services_panel(f, app, area),
+        CurrentTab::Compare => render_compare(f, app, area),
+    }
+}
+
+fn render_overview(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
@@ -146,17 +840,57 @@ fn render_nodes_panel(f: &mut Frame, app: &App, area: Rect) {
         .split(chunks[0]);
 
     render_nodes_table(f, app, top_chunks[0]);
-    render_selected_node_details(f, app, top_chunks[1]);
+    if app.config.prometheus.process_query.is_some() {
+        let detail_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+            .split(top_chunks[1]);
+        render_selected_node_details(f, app, detail_chunks[0]);
+        render_top_processes(f, app, detail_chunks[1]);
+    } else {
+        render_selected_node_details(f, app, top_chunks[1]);
+    }
 
-    // Bottom half: Expanded graphs (50% of column)
-    render_activity_sparklines(f, app, chunks[1]);
+    // Bottom half: history graphs, plus role-specific panels for the node's layout preset
+    let layout = app.detail_node_name().map(|name| app.node_layout(&name)).unwrap_or_default();
+    if layout == NodeLayout::Standard {
+        render_activity_sparklines(f, app, chunks[1]);
+        return;
+    }
+
+    let bottom_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+        .split(chunks[1]);
+    render_activity_sparklines(f, app, bottom_chunks[0]);
+    match layout {
+        NodeLayout::Compute => render_node_gpu_panel(f, app, bottom_chunks[1]),
+        _ => render_pod_density(f, app, bottom_chunks[1]),
+    }
 }
 
 fn render_nodes_table(f: &mut Frame, app: &App, area: Rect) {
+    // Status, CPU, Memory, GPU, Disk, Network, Temp; the name column takes the rest
+    let columns = responsive_columns(area.width, 14, &[8, 10, 10, 6, 10, 14, 7]);
+    let column_count = columns.len();
+
+    let sorted_column = match app.node_sort {
+        NodeSortKey::Name => "Node",
+        NodeSortKey::Cpu => "CPU",
+        NodeSortKey::Memory => "Memory",
+        NodeSortKey::Gpu => "GPU",
+        NodeSortKey::Disk => "Disk",
+        NodeSortKey::Temp => "Temp",
+    };
+    let arrow = if app.node_sort_desc { "▼" } else { "▲" };
+    // No arrow for the default order (by name, ascending)
+    let default_order = app.node_sort == NodeSortKey::Name && !app.node_sort_desc;
     let header_cells = ["Node", "Status", "CPU", "Memory", "GPU", "Disk", "Network", "Temp"]
         .iter()
+        .take(column_count)
         .map(|h| {
-            Cell::from(*h)
+            let label = if *h == sorted_column && !default_order { format!("{} {}", h, arrow) } else { h.to_string() };
+            Cell::from(label)
                 .style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
         });
 
@@ -164,28 +898,50 @@ fn render_nodes_table(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().bg(app.theme_colors.border))
         .height(1);
 
-    let rows = app.nodes.iter().enumerate().map(|(i, (name, node))| {
+    // Node index of each table row; None for group headings
+    let node_rows = app.node_rows();
+    let mut node_count = 0;
+    let row_indices: Vec<Option<usize>> = node_rows
+        .iter()
+        .map(|row| match row {
+            NodeRow::Group(_) => None,
+            NodeRow::Node(_) => {
+                node_count += 1;
+                Some(node_count - 1)
+            }
+        })
+        .collect();
+    let selected_row = row_indices.iter().position(|index| *index == Some(app.selected_node_index)).unwrap_or(0);
+    let (start, end) = visible_rows(node_rows.len(), table_capacity(area), selected_row);
+    app.nodes_table_area.set(area);
+    app.nodes_table_offset.set(start);
+
+    let rows = node_rows.iter().zip(&row_indices).skip(start).take(end - start).map(|(row, index)| {
+        let name = match row {
+            NodeRow::Group(group) => {
+                let label = app.config.nodes.group_by.as_deref().unwrap_or_default();
+                return Row::new(vec![Cell::from(format!("▾ {}: {}", label, group))])
+                    .style(Style::default().fg(app.theme_colors.secondary).add_modifier(Modifier::BOLD));
+            }
+            NodeRow::Node(name) => name,
+        };
+        let i = index.unwrap_or_default();
+        let node = &app.nodes[name];
         let is_selected = i == app.selected_node_index;
         let is_active_panel = app.active_panel == ActivePanel::Nodes;
 
-        let cpu_color = if node.cpu_usage > 80.0 { app.theme_colors.gauge_danger }
-                        else if node.cpu_usage > 60.0 { app.theme_colors.gauge_warning }
-                        else { app.theme_colors.gauge_good };
+        let cpu_color = usage_color(app, node.cpu_usage);
 
-        let mem_color = if node.memory_usage > 80.0 { app.theme_colors.gauge_danger }
-                        else if node.memory_usage > 60.0 { app.theme_colors.gauge_warning }
-                        else { app.theme_colors.gauge_good };
+        let mem_color = usage_color(app, node.memory_usage);
 
-        let gpu_color = if node.gpu_usage > 80.0 { app.theme_colors.gauge_danger }
-                        else if node.gpu_usage > 60.0 { app.theme_colors.gauge_warning }
-                        else { app.theme_colors.gauge_good };
+        let gpu_color = usage_color(app, node.gpu_usage);
 
-        let disk_color = if node.disk_usage > 80.0 { app.theme_colors.gauge_danger }
-                         else if node.disk_usage > 60.0 { app.theme_colors.gauge_warning }
-                         else { app.theme_colors.gauge_good };
+        let disk_color = usage_color(app, node.disk_usage);
 
-        let temp_color = if node.temperature > 80.0 { app.theme_colors.gauge_danger }
-                         else if node.temperature > 60.0 { app.theme_colors.gauge_warning }
+        let unit = app.node_temperature_unit(name);
+        let (temperature, _) = convert_temperature(node.temperature, unit);
+        let temp_color = if temperature > convert_temperature(80.0, unit).0 { app.theme_colors.gauge_danger }
+                         else if temperature > convert_temperature(60.0, unit).0 { app.theme_colors.gauge_warning }
                          else { app.theme_colors.gauge_good };
 
         let data_source = match app.connection_status {
@@ -194,36 +950,46 @@ fn render_nodes_table(f: &mut Frame, app: &App, area: Rect) {
         };
 
         let cells = vec![
-            Cell::from(if is_selected && is_active_panel { format!("► {} {}", name, data_source) } else { format!("{} {}", name, data_source) }),
+            // ⌂ marks the node this dashboard runs on; its figures include our own overhead
+            Cell::from(format!(
+                "{}{}{} {}",
+                if is_selected && is_active_panel { "► " } else { "" },
+                name,
+                if app.local_node.as_deref() == Some(name.as_str()) { " ⌂" } else { "" },
+                data_source,
+            )),
             Cell::from(node.status.clone()),
-            Cell::from(format!("{:.1}%", node.cpu_usage)).style(Style::default().fg(cpu_color)),
-            Cell::from(format!("{:.1}%", node.memory_usage)).style(Style::default().fg(mem_color)),
-            Cell::from(if node.gpu_usage > 0.0 { format!("{:.1}%", node.gpu_usage) } else { "N/A".to_string() })
-                .style(Style::default().fg(gpu_color)),
-            Cell::from(format!("{:.1}%", node.disk_usage)).style(Style::default().fg(disk_color)),
-            Cell::from(format!("↓{:.0} ↑{:.0}MB/s", node.network_rx, node.network_tx)),
-            Cell::from(format!("{:.1}°C", node.temperature)).style(Style::default().fg(temp_color)),
+            node_metric_cell(app, node, "cpu", format_node_cpu(app, node), cpu_color),
+            // Swap in use usually means the node is under memory pressure
+            if node.swap_usage > 0.0 {
+                node_metric_cell(app, node, "memory", format!("{} ⚠", format_node_memory(app, node)), app.theme_colors.gauge_danger)
+                    .bold()
+            } else {
+                node_metric_cell(app, node, "memory", format_node_memory(app, node), mem_color)
+            },
+            node_metric_cell(app, node, "gpu", localized(app, format!("{:.1}%", node.gpu_usage)), gpu_color),
+            node_metric_cell(app, node, "disk", format_node_disk(app, node), disk_color),
+            node_metric_cell(app, node, "network", format_node_network(app, name, node), app.theme_colors.foreground),
+            node_metric_cell(app, node, "temperature", format_node_temperature(app, name, node.temperature), temp_color),
         ];
 
-        let style = if is_selected && is_active_panel {
-            Style::default().bg(app.theme_colors.highlight).add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-        };
-
-        Row::new(cells).style(style)
+        let is_hovered = app.hovered_row == Some((ActivePanel::Nodes, i));
+        let cells = cells.into_iter().take(column_count).collect();
+        styled_row(app, cells, is_selected && is_active_panel, is_hovered)
     });
 
-    let table = Table::new(rows, [Constraint::Min(14), Constraint::Min(8), Constraint::Min(6),
-                                   Constraint::Min(7), Constraint::Min(6), Constraint::Min(6),
-                                   Constraint::Min(12), Constraint::Min(7)])
+    let table = Table::new(rows, columns)
         .header(header)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Nodes")
+                .title(format!("Nodes (cluster CPU {:.1}%){}", app.cluster_cpu_weighted(),
+                    pagination_label(start, end, node_rows.len())))
                 .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
-                .border_style(if app.active_panel == ActivePanel::Nodes {
+                .border_style(if app.is_flashing(ActivePanel::Nodes) {
+                    // Values just changed
+                    Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)
+                } else if app.active_panel == ActivePanel::Nodes {
                     Style::default().fg(app.theme_colors.info)
                 } else {
                     Style::default().fg(app.theme_colors.border)
@@ -234,57 +1000,171 @@ fn render_nodes_table(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(table, area);
 }
 
-fn render_node_resources(f: &mut Frame, app: &App, area: Rect) {
+/// Receive errors/drops per second, flagged while they are increasing
+fn network_issues_line(app: &App, node: &crate::mock_data::NodeMetrics) -> Line<'static> {
+    let issue_style = |rate: f64, color: Color| {
+        let stale = node.stale_metrics.contains("network_errors") || node.stale_metrics.contains("network_drops");
+        if rate > 0.0 {
+            metric_style(app, stale, color).add_modifier(Modifier::BOLD)
+        } else {
+            metric_style(app, stale, app.theme_colors.success)
+        }
+    };
+    Line::from(vec![
+        Span::styled("Errs: ", Style::default().fg(app.theme_colors.text_muted)),
+        Span::styled(localized(app, format!("{:.1}/s", node.network_errors)), issue_style(node.network_errors, app.theme_colors.gauge_danger)),
+        Span::styled("  Drops: ", Style::default().fg(app.theme_colors.text_muted)),
+        Span::styled(localized(app, format!("{:.1}/s", node.network_drops)), issue_style(node.network_drops, app.theme_colors.gauge_warning)),
+    ])
+}
+
+fn render_node_gpu_panel(f: &mut Frame, app: &App, area: Rect) {
+    let Some(node_name) = app.detail_node_name() else {
+        return;
+    };
+    let node = &app.nodes[&node_name];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("GPU · {}", node.gpu_model))
+        .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(app.theme_colors.border));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if node.gpu_memory_total == 0 {
+        let placeholder = Paragraph::new("No GPU metrics")
+            .style(Style::default().fg(app.theme_colors.text_muted));
+        f.render_widget(placeholder, inner);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(6), Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-        .split(area);
+        .constraints([Constraint::Length(2), Constraint::Length(2), Constraint::Min(0)].as_ref())
+        .split(inner);
+
+    let vram_total_gb = node.gpu_memory_total as f64 / (1024.0 * 1024.0 * 1024.0);
+
+    let utilization = Gauge::default()
+        .block(Block::default().title("Utilization"))
+        .gauge_style(Style::default().fg(usage_color(app, node.gpu_usage)).bg(app.theme_colors.background))
+        .label(localized(app, format!("{:.1}%", node.gpu_usage)))
+        .ratio((node.gpu_usage / 100.0).clamp(0.0, 1.0));
+    f.render_widget(utilization, chunks[0]);
+
+    let vram = Gauge::default()
+        .block(Block::default().title("VRAM"))
+        .gauge_style(Style::default().fg(usage_color(app, node.gpu_memory)).bg(app.theme_colors.background))
+        .label(localized(app, format!("{:.1}/{:.0}GB", node.gpu_memory / 100.0 * vram_total_gb, vram_total_gb)))
+        .ratio((node.gpu_memory / 100.0).clamp(0.0, 1.0));
+    f.render_widget(vram, chunks[1]);
+}
 
-    // Selected node details
-    render_selected_node_details(f, app, chunks[0]);
+fn render_pod_density(f: &mut Frame, app: &App, area: Rect) {
+    let service_names = app.filtered_service_names();
+    let ready: u32 = service_names.iter().map(|name| app.services[name].ready_replicas).sum();
+    let desired: u32 = service_names.iter().map(|name| app.services[name].replicas).sum();
 
-    // Resource usage gauges
-    render_resource_gauges(f, app, chunks[1]);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Pods ready: ", Style::default().fg(app.theme_colors.text_muted)),
+            Span::styled(
+                format!("{}/{}", ready, desired),
+                Style::default().fg(if ready == desired { app.theme_colors.success } else { app.theme_colors.warning }),
+            ),
+        ]),
+        Line::from(""),
+    ];
+    for name in &service_names {
+        let service = &app.services[name];
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:<14}", name)),
+            Span::styled(
+                format!("{}/{}", service.ready_replicas, service.replicas),
+                Style::default().fg(if service.ready_replicas == service.replicas { app.theme_colors.success } else { app.theme_colors.warning }),
+            ),
+        ]));
+    }
 
-    // History sparklines
-    render_activity_sparklines(f, app, chunks[2]);
+    let pods = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Workloads ({} services)", service_names.len()))
+                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(app.theme_colors.border)),
+        );
+    f.render_widget(pods, area);
 }
 
 fn render_selected_node_details(f: &mut Frame, app: &App, area: Rect) {
-    let node_names: Vec<String> = app.nodes.keys().cloned().collect();
-    if node_names.is_empty() || app.selected_node_index >= node_names.len() {
+    let Some(node_name) = app.detail_node_name() else {
         return;
-    }
+    };
+    let node = &app.nodes[&node_name];
 
-    let node_name = &node_names[app.selected_node_index];
-    let node = &app.nodes[node_name];
+    // Load above the core count means work is queueing (often IO wait)
+    let load_color = if node.load_avg[0] > node.cpu_cores as f64 { app.theme_colors.gauge_danger }
+                     else { app.theme_colors.foreground };
 
     // Create compact hardware specs text
-    let hardware_specs = vec![
+    let mut hardware_specs = vec![
         Line::from(vec![
             Span::styled("Node: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{} | {} ({}c/{}t)", node_name, node.cpu_model, node.cpu_cores, node.cpu_threads)),
+            Span::raw(format!("{} | {} ({}c/{}t) | ", node_name, node.cpu_model, node.cpu_cores, node.cpu_threads)),
+            Span::styled(
+                format!("load {:.1} {:.1} {:.1}", node.load_avg[0], node.load_avg[1], node.load_avg[2]),
+                metric_style(app, node.stale_metrics.contains("load"), load_color),
+            ),
         ]),
         Line::from(vec![
             Span::styled("RAM: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
             Span::raw(format!("{:.0}GB | ", node.memory_total_gb)),
+            Span::styled("Swap: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                localized(app, format!("{:.1}%", node.swap_usage)),
+                if node.swap_usage > 0.0 {
+                    metric_style(app, node.stale_metrics.contains("swap"), app.theme_colors.gauge_danger).add_modifier(Modifier::BOLD)
+                } else {
+                    metric_style(app, node.stale_metrics.contains("swap"), app.theme_colors.foreground)
+                },
+            ),
+            Span::raw(" | "),
             Span::styled("GPU: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
             Span::raw(&node.gpu_model),
         ]),
         Line::from(vec![
             Span::styled("Storage: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{:.0}GB | {:.1}°C | ", node.disk_total_gb, node.temperature)),
+            Span::raw(format!("{:.0}GB | {} | ", node.disk_total_gb, format_node_temperature(app, &node_name, node.temperature))),
             Span::styled("Usage: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
-            Span::raw(format!("CPU {:.1}% | Mem {:.1}%", node.cpu_usage, node.memory_usage)),
+            Span::raw(format!("CPU {} | Mem {}", format_node_cpu(app, node), format_node_memory(app, node))),
         ]),
     ];
+    if let Some(baseline) = app.active_baseline() {
+        hardware_specs.push(match baseline.nodes.get(&node_name) {
+            Some(then) => baseline_line(app, baseline, vec![
+                ("CPU", node.cpu_usage - then.cpu_usage, "%"),
+                ("Mem", node.memory_usage - then.memory_usage, "%"),
+                ("GPU", node.gpu_usage - then.gpu_usage, "%"),
+                ("Disk", node.disk_usage - then.disk_usage, "%"),
+                {
+                    let unit = app.node_temperature_unit(&node_name);
+                    let (now, symbol) = convert_temperature(node.temperature, unit);
+                    ("Temp", now - convert_temperature(then.temperature, unit).0, symbol)
+                },
+            ]),
+            None => baseline_line(app, baseline, Vec::new()),
+        });
+    }
 
     let details = Paragraph::new(hardware_specs)
         .style(Style::default().fg(app.theme_colors.foreground))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Hardware Specs")
+                .title(pinned_title(app, "Hardware Specs", &node_name))
+                .title_bottom(Line::from(format!(" up {} ", crate::format::format_uptime(node.uptime))).right_aligned())
                 .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
                 .border_style(Style::default().fg(app.theme_colors.border)),
         );
@@ -292,87 +1172,100 @@ fn render_selected_node_details(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(details, area);
 }
 
-fn render_resource_gauges(f: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Min(0),
-        ].as_ref())
-        .split(area);
+/// The detail node's busiest process groups, from `prometheus.process_query`
+fn render_top_processes(f: &mut Frame, app: &App, area: Rect) {
+    let node = app.detail_node_name().and_then(|name| app.nodes.get(&name));
+    let processes = node.map(|node| node.top_processes.as_slice()).unwrap_or_default();
 
-    let node_names: Vec<String> = app.nodes.keys().cloned().collect();
-    if node_names.is_empty() || app.selected_node_index >= node_names.len() {
-        return;
+    let mut lines: Vec<Line> = processes
+        .iter()
+        .take(app.config.ui.top_processes)
+        .enumerate()
+        .map(|(rank, process)| {
+            Line::from(vec![
+                Span::styled(format!("{}. ", rank + 1), Style::default().fg(app.theme_colors.text_muted)),
+                Span::styled(format!("{:>6.1}% ", process.cpu_usage), Style::default().fg(usage_color(app, process.cpu_usage.min(100.0)))),
+                Span::raw(process.name.clone()),
+            ])
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled("No process data", Style::default().fg(app.theme_colors.text_muted))));
     }
 
-    let node = &app.nodes[&node_names[app.selected_node_index]];
+    let title = with_query(app, "Top Processes", app.config.prometheus.process_query.as_deref().unwrap_or_default());
+    let panel = Paragraph::new(lines)
+        .style(Style::default().fg(app.theme_colors.foreground))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(app.theme_colors.border)),
+        );
+    f.render_widget(panel, area);
+}
 
-    // CPU Usage
-    let cpu_gauge = Gauge::default()
-        .block(Block::default().borders(Borders::ALL).title("CPU Usage").border_style(Style::default().fg(app.theme_colors.border)))
-        .gauge_style(
-            Style::default()
-                .fg(if node.cpu_usage > 80.0 { app.theme_colors.gauge_danger } else if node.cpu_usage > 60.0 { app.theme_colors.gauge_warning } else { app.theme_colors.gauge_good })
-                .bg(app.theme_colors.background)
-                .add_modifier(Modifier::BOLD),
-        )
-        .label(format!("{:.1}%", node.cpu_usage))
-        .ratio(node.cpu_usage / 100.0);
-    f.render_widget(cpu_gauge, chunks[0]);
-
-    // Memory Usage
-    let memory_gauge = Gauge::default()
-        .block(Block::default().borders(Borders::ALL).title("Memory Usage").border_style(Style::default().fg(app.theme_colors.border)))
-        .gauge_style(
-            Style::default()
-                .fg(if node.memory_usage > 80.0 { app.theme_colors.gauge_danger } else if node.memory_usage > 60.0 { app.theme_colors.gauge_warning } else { app.theme_colors.gauge_good })
-                .bg(app.theme_colors.background)
-                .add_modifier(Modifier::BOLD),
-        )
-        .label(format!("{:.1}%", node.memory_usage))
-        .ratio(node.memory_usage / 100.0);
-    f.render_widget(memory_gauge, chunks[1]);
-
-    // GPU Usage (only for nodes with GPU)
-    if node.gpu_memory_total > 0 {
-        let gpu_gauge = Gauge::default()
-            .block(Block::default().borders(Borders::ALL).title("GPU Usage").border_style(Style::default().fg(app.theme_colors.border)))
-            .gauge_style(
-                Style::default()
-                    .fg(if node.gpu_usage > 80.0 { app.theme_colors.gauge_danger } else if node.gpu_usage > 60.0 { app.theme_colors.gauge_warning } else { app.theme_colors.gauge_good })
-                    .bg(app.theme_colors.background)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .label(format!("{:.1}%", node.gpu_usage))
-            .ratio(node.gpu_usage / 100.0);
-        f.render_widget(gpu_gauge, chunks[2]);
-    }
+/// The characters of a rendered frame without styling, one line per row
+/// with trailing spaces trimmed
+pub fn buffer_text(buffer: &Buffer) -> String {
+    buffer
+        .content()
+        .chunks(buffer.area.width.max(1) as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A graph's samples, or just the current value until history builds up
+fn samples_or_current(history: Option<&VecDeque<f64>>, current: f64) -> impl Iterator<Item = f64> + '_ {
+    let history = history.filter(|history| !history.is_empty());
+    history.is_none().then_some(current).into_iter().chain(history.into_iter().flatten().copied())
+}
+
+/// Bar resolution of `GraphStyle::Bar`; the sparkline scales this to the area
+const BAR_LEVELS: f64 = 20.0;
+
+/// Draw a series of values between 0 and `max` in the configured
+/// `ui.graph_style`, the same way for every metric graph
+fn render_graph(f: &mut Frame, app: &App, area: Rect, block: Block, values: impl IntoIterator<Item = f64>, max: f64, color: Color) {
+    let marker = match app.config.ui.graph_style {
+        GraphStyle::Bar => {
+            let data: Vec<u64> = values.into_iter().map(|value| (value.clamp(0.0, max) * (BAR_LEVELS / max)) as u64).collect();
+            let sparkline = Sparkline::default()
+                .block(block)
+                .data(&data)
+                .style(Style::default().fg(color))
+                .max(BAR_LEVELS as u64);
+            f.render_widget(sparkline, area);
+            return;
+        }
+        GraphStyle::Line => symbols::Marker::HalfBlock,
+        GraphStyle::Braille => symbols::Marker::Braille,
+    };
 
-    // Disk Usage
-    let disk_gauge = Gauge::default()
-        .block(Block::default().borders(Borders::ALL).title("Disk Usage").border_style(Style::default().fg(app.theme_colors.border)))
-        .gauge_style(
-            Style::default()
-                .fg(if node.disk_usage > 80.0 { app.theme_colors.gauge_danger } else if node.disk_usage > 60.0 { app.theme_colors.gauge_warning } else { app.theme_colors.gauge_good })
-                .bg(app.theme_colors.background)
-                .add_modifier(Modifier::BOLD),
-        )
-        .label(format!("{:.1}%", node.disk_usage))
-        .ratio(node.disk_usage / 100.0);
-    f.render_widget(disk_gauge, chunks[3]);
+    let mut points: Vec<(f64, f64)> = values.into_iter().enumerate().map(|(i, value)| (i as f64, value.clamp(0.0, max))).collect();
+    // A line needs two ends; draw a lone value as a flat one
+    if let [(_, value)] = points[..] {
+        points.push((1.0, value));
+    }
+    let dataset = Dataset::default()
+        .marker(marker)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(&points);
+    let chart = Chart::new(vec![dataset])
+        .block(block)
+        .x_axis(Axis::default().bounds([0.0, points.len().saturating_sub(1).max(1) as f64]))
+        .y_axis(Axis::default().bounds([0.0, max]));
+    f.render_widget(chart, area);
 }
 
 fn render_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
-    let node_names: Vec<String> = app.nodes.keys().cloned().collect();
-    if node_names.is_empty() || app.selected_node_index >= node_names.len() {
+    let Some(node_name) = app.detail_node_name() else {
         return;
-    }
-
-    let node_name = &node_names[app.selected_node_index];
+    };
+    let node_name = &node_name;
     let node = &app.nodes[node_name];
 
     // Create two-column layout for better visualization
@@ -393,44 +1286,14 @@ fn render_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(chunks[1]);
 
-    // Node CPU History - Enhanced visibility
+    // Node CPU History, or just the current usage until history builds up
     if let Some(history) = app.node_history.get(node_name) {
-        if !history.is_empty() {
-            // Create bar-style data for better visibility
-            let cpu_data: Vec<u64> = history.iter().map(|&x| {
-                // Convert to 0-20 range for better bar visibility
-                (x * 0.2) as u64
-            }).collect();
-
-            let cpu_sparkline = Sparkline::default()
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title(format!("CPU Usage ({}%)", node.cpu_usage as u32))
-                        .title_style(Style::default().fg(app.theme_colors.primary))
-                        .border_style(Style::default().fg(app.theme_colors.border)),
-                )
-                .data(&cpu_data)
-                .style(Style::default().fg(app.theme_colors.success))
-                .max(20); // Max 100% * 0.2 = 20
-            f.render_widget(cpu_sparkline, left_chunks[0]);
-        } else {
-            // Show current usage as a simple bar when no history
-            let current_cpu = (node.cpu_usage * 0.2) as u64;
-            let cpu_data = vec![current_cpu];
-            let cpu_sparkline = Sparkline::default()
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title(format!("CPU Usage ({}%)", node.cpu_usage as u32))
-                        .title_style(Style::default().fg(app.theme_colors.primary))
-                        .border_style(Style::default().fg(app.theme_colors.border)),
-                )
-                .data(&cpu_data)
-                .style(Style::default().fg(app.theme_colors.success))
-                .max(20);
-            f.render_widget(cpu_sparkline, left_chunks[0]);
-        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(with_query(app, windowed(app, format!("CPU Usage ({}%)", node.cpu_usage as u32)), &app.config.prometheus.node_queries.cpu_usage))
+            .title_style(Style::default().fg(app.theme_colors.primary))
+            .border_style(Style::default().fg(app.theme_colors.border));
+        render_graph(f, app, left_chunks[0], block, samples_or_current(Some(history), node.cpu_usage), 100.0, app.theme_colors.success);
     } else {
         // Show placeholder when no history exists yet
         let placeholder = Paragraph::new(format!("CPU: {:.1}% | Initializing...", node.cpu_usage))
@@ -438,56 +1301,43 @@ fn render_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("CPU Usage")
+                    .title(with_query(app, "CPU Usage", &app.config.prometheus.node_queries.cpu_usage))
                     .title_style(Style::default().fg(app.theme_colors.primary))
                     .border_style(Style::default().fg(app.theme_colors.border)),
             );
         f.render_widget(placeholder, left_chunks[0]);
     }
 
-    // Memory usage with better visibility
-    let memory_data: Vec<u64> = (0..30).map(|i| {
-        let base = app.nodes[node_name].memory_usage;
-        // Add some variation to simulate memory fluctuations
-        let variation = (i as f64 * 0.1).sin() * 3.0;
-        ((base + variation).max(0.0).min(100.0) * 0.2) as u64 // Scale to 0-20 range
-    }).collect();
-
-    let memory_sparkline = Sparkline::default()
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!("Memory Usage ({}%)", node.memory_usage as u32))
-                .title_style(Style::default().fg(app.theme_colors.info))
-                .border_style(Style::default().fg(app.theme_colors.border)),
-        )
-        .data(&memory_data)
-        .style(Style::default().fg(app.theme_colors.info))
-        .max(20); // Max 100% * 0.2 = 20
-    f.render_widget(memory_sparkline, left_chunks[1]);
+    // Memory history, or just the current usage until history builds up
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(with_query(app, windowed(app, format!("Memory Usage ({}%)", node.memory_usage as u32)), &app.config.prometheus.node_queries.memory_usage))
+        .title_style(Style::default().fg(app.theme_colors.info))
+        .border_style(Style::default().fg(app.theme_colors.border));
+    render_graph(f, app, left_chunks[1], block, samples_or_current(app.memory_history.get(node_name), node.memory_usage), 100.0, app.theme_colors.info);
 
     // Network TX (outbound) with better visibility and correct values
-    let network_tx_base = app.nodes[node_name].network_tx;
+    let network_unit = app.node_network_unit(node_name);
 
     let network_tx_text = Paragraph::new(vec![
         Line::from(Span::styled("Network Status", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))),
         Line::from(vec![]),
         Line::from(vec![
             Span::styled("↑ TX: ", Style::default().fg(app.theme_colors.text_muted)),
-            Span::styled(format!("{:.1} MB/s", network_tx_base), Style::default().fg(app.theme_colors.warning).add_modifier(Modifier::BOLD))
+            Span::styled(localized(app, format_network_rate(node.network_tx, network_unit)), Style::default().fg(app.theme_colors.warning).add_modifier(Modifier::BOLD))
         ]),
         Line::from(vec![
             Span::styled("↓ RX: ", Style::default().fg(app.theme_colors.text_muted)),
-            Span::styled(format!("{:.1} MB/s", node.network_rx), Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+            Span::styled(localized(app, format_network_rate(node.network_rx, network_unit)), Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
         ]),
         Line::from(vec![]),
-        Line::from(Span::styled("Real-time network I/O", Style::default().fg(app.theme_colors.text_muted))),
+        network_issues_line(app, node),
     ])
         .style(Style::default().fg(app.theme_colors.foreground))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Network")
+                .title(with_query(app, "Network", &app.config.prometheus.node_queries.network_rx))
                 .title_style(Style::default().fg(app.theme_colors.primary))
                 .border_style(Style::default().fg(app.theme_colors.border)),
         )
@@ -500,11 +1350,11 @@ fn render_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
         Line::from(vec![]),
         Line::from(vec![
             Span::styled("Disk: ", Style::default().fg(app.theme_colors.text_muted)),
-            Span::styled(format!("{:.1}%", node.disk_usage), Style::default().fg(app.theme_colors.info))
+            Span::styled(format_node_disk(app, node), Style::default().fg(app.theme_colors.info))
         ]),
         Line::from(vec![
             Span::styled("Temp: ", Style::default().fg(app.theme_colors.text_muted)),
-            Span::styled(format!("{:.1}°C", node.temperature), Style::default().fg(app.theme_colors.gauge_warning))
+            Span::styled(format_node_temperature(app, node_name, node.temperature), Style::default().fg(app.theme_colors.gauge_warning))
         ]),
         Line::from(vec![]),
         Line::from(Span::styled("Hardware monitoring", Style::default().fg(app.theme_colors.text_muted))),
@@ -530,31 +1380,206 @@ fn render_services_panel(f: &mut Frame, app: &App, area: Rect) {
     // Top 50%: Services table
     render_services_table(f, app, chunks[0]);
 
-    // Bottom 50%: Service graphs, health, and logs
-    let service_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(35), Constraint::Percentage(25)].as_ref())
-        .split(chunks[1]);
+    // Bottom 50%: Service graphs, health, logs and, when configured, dependencies
+    if app.config.dependencies.is_empty() {
+        let service_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(35), Constraint::Percentage(25)].as_ref())
+            .split(chunks[1]);
 
-    render_service_activity_sparklines(f, app, service_chunks[0]);
-    render_service_health_info(f, app, service_chunks[1]);
-    render_service_logs(f, app, service_chunks[2]);
+        render_service_activity_sparklines(f, app, service_chunks[0]);
+        render_service_health_info(f, app, service_chunks[1]);
+        render_service_logs(f, app, service_chunks[2]);
+    } else {
+        let service_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(25), Constraint::Percentage(20), Constraint::Percentage(25)].as_ref())
+            .split(chunks[1]);
+
+        render_service_activity_sparklines(f, app, service_chunks[0]);
+        render_service_health_info(f, app, service_chunks[1]);
+        render_service_logs(f, app, service_chunks[2]);
+        render_service_dependencies(f, app, service_chunks[3]);
+    }
 }
 
-fn render_service_health_info(f: &mut Frame, app: &App, area: Rect) {
-    // Get selected service using the same sorting as the services table
-    let mut filtered_services: Vec<_> = app.services
-        .iter()
-        .filter(|(_name, service)| {
-            // Show all homelab services
-            service.namespace == "homelab"
-        })
-        .collect();
+/// Nodes and services in one list with a type column, filtered by the search
+fn render_unified_list(f: &mut Frame, app: &App, area: Rect) {
+    let items = app.unified_items();
+    let (start, end) = visible_rows(items.len(), table_capacity(area), app.unified_index);
+
+    let header_cells = ["Type", "Name", "Status", "CPU", "Memory", "Details"]
+        .map(|h| Cell::from(h).style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)));
+    let header = Row::new(header_cells)
+        .style(Style::default().bg(app.theme_colors.border))
+        .height(1);
+    let columns = [
+        Constraint::Length(8),
+        Constraint::Min(18),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Min(30),
+    ];
+
+    let rows = items.iter().enumerate().skip(start).take(end - start).map(|(i, item)| {
+        let cells = match item {
+            UnifiedItem::Node(name) => {
+                let node = &app.nodes[name];
+                let gpu = if app.node_metric_availability(node, "gpu") == MetricAvailability::NotApplicable {
+                    "—".to_string()
+                } else {
+                    localized(app, format!("{:.1}%", node.gpu_usage))
+                };
+                vec![
+                    Cell::from("node").style(Style::default().fg(app.theme_colors.secondary)),
+                    Cell::from(name.as_str()),
+                    Cell::from(node.status.as_str()),
+                    Cell::from(localized(app, format!("{:.1}%", node.cpu_usage))).style(metric_style(app, node.stale_metrics.contains("cpu"), usage_color(app, node.cpu_usage))),
+                    Cell::from(localized(app, format!("{:.1}%", node.memory_usage))).style(metric_style(app, node.stale_metrics.contains("memory"), usage_color(app, node.memory_usage))),
+                    Cell::from(format!("GPU {} · Disk {} · {}", gpu, localized(app, format!("{:.1}%", node.disk_usage)), format_node_temperature(app, name, node.temperature))),
+                ]
+            }
+            UnifiedItem::Service(name) => {
+                let service = &app.services[name];
+                let status_color = if service.status == "Running" { app.theme_colors.success } else { app.theme_colors.error };
+                vec![
+                    Cell::from("service").style(Style::default().fg(app.theme_colors.info)),
+                    Cell::from(name.as_str()),
+                    Cell::from(service.status.as_str()).style(metric_style(app, service.stale_metrics.contains("status"), status_color)),
+                    Cell::from(localized(app, format!("{:.1}%", service.cpu_usage))).style(metric_style(app, service.stale_metrics.contains("cpu"), usage_color(app, service.cpu_usage))),
+                    Cell::from(localized(app, format!("{:.1}%", service.memory_usage))).style(metric_style(app, false, usage_color(app, service.memory_usage))),
+                    Cell::from(format!(
+                        "{} · {} rps · {:.0}ms · {}/{} ready",
+                        service.namespace,
+                        crate::format::count(service.requests_per_sec, &app.config.ui),
+                        service.response_time,
+                        service.ready_replicas,
+                        service.replicas,
+                    )),
+                ]
+            }
+        };
+        styled_row(app, cells, i == app.unified_index, false)
+    });
+
+    let search = if app.unified_typing {
+        format!(" · /{}▏", app.unified_query)
+    } else if !app.unified_query.is_empty() {
+        format!(" · /{}", app.unified_query)
+    } else {
+        " · / to search".to_string()
+    };
+    let table = Table::new(rows, columns)
+        .header(header)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("All ({}){}{}", items.len(), search, pagination_label(start, end, items.len())))
+                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(app.theme_colors.info)),
+        );
+
+    f.render_widget(table, area);
+}
+
+/// History of the nodes and services picked with Space, one row each
+fn render_compare(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Compare ({} selected)", app.selected_items.len()))
+        .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+        .border_style(Style::default().fg(app.theme_colors.border));
+
+    if app.selected_items.is_empty() {
+        let hint = Paragraph::new("Select nodes or services with Space to compare their history here")
+            .style(Style::default().fg(app.theme_colors.text_muted))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(hint, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, app.selected_items.len() as u32); app.selected_items.len()])
+        .split(inner);
+
+    for (name, row) in app.selected_items.iter().zip(rows.iter()) {
+        let (title, history) = if let Some(node) = app.nodes.get(name) {
+            (format!("⬢ {} · CPU {:.1}% · Mem {:.1}%", name, node.cpu_usage, node.memory_usage), app.node_history.get(name))
+        } else if let Some(service) = app.services.get(name) {
+            (format!("◆ {} · CPU {:.1}% · Mem {:.1}%", name, service.cpu_usage, service.memory_usage), app.service_history.get(name))
+        } else {
+            (format!("{} (gone)", name), None)
+        };
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .title(title)
+            .title_style(Style::default().fg(app.theme_colors.foreground))
+            .border_style(Style::default().fg(app.theme_colors.border));
+        render_graph(f, app, *row, block, history.into_iter().flatten().copied(), 100.0, app.theme_colors.success);
+    }
+}
+
+fn render_service_dependencies(f: &mut Frame, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+
+    if let Some(service_name) = app.detail_service_name() {
+        lines.push(dependency_line(app, 0, &service_name));
+
+        for (heading, upstream) in [("Depends on", true), ("Needed by", false)] {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(heading, Style::default().fg(app.theme_colors.primary))));
+
+            let tree = app.dependency_tree(&service_name, upstream);
+            if tree.is_empty() {
+                lines.push(Line::from(Span::styled("  (none)", Style::default().fg(app.theme_colors.text_muted))));
+            }
+            for (depth, name) in tree {
+                lines.push(dependency_line(app, depth, &name));
+            }
+        }
+    } else {
+        lines.push(Line::from(Span::styled("No service selected", Style::default().fg(app.theme_colors.text_muted))));
+    }
+
+    let dependencies = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Dependencies")
+                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(app.theme_colors.border)),
+        );
+
+    f.render_widget(dependencies, area);
+}
 
-    // Sort alphabetically by service name - same as services table
-    filtered_services.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+/// One entry of the dependency tree, indented by depth and colored by health
+fn dependency_line(app: &App, depth: usize, service_name: &str) -> Line<'static> {
+    let health = app.services.get(service_name).map(|service| app.health_status(service));
+    let color = match health {
+        Some("Healthy") => app.theme_colors.success,
+        Some("Starting") => app.theme_colors.secondary,
+        Some("Degraded") => app.theme_colors.gauge_warning,
+        Some("Unhealthy") => app.theme_colors.gauge_danger,
+        _ => app.theme_colors.text_muted,
+    };
+    let branch = if depth == 0 { String::new() } else { format!("{}└ ", "  ".repeat(depth)) };
+
+    Line::from(vec![
+        Span::raw(format!("{}{} ", branch, service_name)),
+        Span::styled(format!("● {}", health.unwrap_or("Unknown")), Style::default().fg(color)),
+    ])
+}
 
-    if filtered_services.is_empty() || app.selected_service_index >= filtered_services.len() {
+fn render_service_health_info(f: &mut Frame, app: &App, area: Rect) {
+    // Selected service (same ordering as the services table), unless one is pinned
+    let Some(service_name) = app.detail_service_name() else {
         let placeholder = Paragraph::new("No service selected")
             .style(Style::default().fg(app.theme_colors.text_muted))
             .block(
@@ -566,16 +1591,15 @@ fn render_service_health_info(f: &mut Frame, app: &App, area: Rect) {
             );
         f.render_widget(placeholder, area);
         return;
-    }
+    };
 
-    let (service_name, service) = &filtered_services[app.selected_service_index];
-    render_service_health(f, app, service_name, service, area);
+    let service = &app.services[&service_name];
+    render_service_health(f, app, &service_name, service, area);
 }
 
 fn render_service_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
     // Get selected service
-    let service_names: Vec<String> = app.services.keys().cloned().collect();
-    if service_names.is_empty() || app.selected_service_index >= service_names.len() {
+    let Some(service_name) = app.detail_service_name() else {
         let placeholder = Paragraph::new("No services available")
             .style(Style::default().fg(app.theme_colors.text_muted))
             .block(
@@ -587,9 +1611,9 @@ fn render_service_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
             );
         f.render_widget(placeholder, area);
         return;
-    }
+    };
 
-    let service_name = &service_names[app.selected_service_index];
+    let service_name = &service_name;
     let service = &app.services[service_name];
 
     // Create two-column layout for service graphs
@@ -598,88 +1622,39 @@ fn render_service_activity_sparklines(f: &mut Frame, app: &App, area: Rect) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(area);
 
-    // Service CPU History with improved visibility
+    // Service CPU History, or just the current usage until history builds up
     if let Some(history) = app.service_history.get(service_name) {
-        if !history.is_empty() {
-            // Use same scaling as nodes for consistency
-            let cpu_data: Vec<u64> = history.iter().map(|&x| (x * 0.2) as u64).collect();
-            let cpu_sparkline = Sparkline::default()
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title(format!("CPU Usage ({}%)", service.cpu_usage as u32))
-                        .title_style(Style::default().fg(app.theme_colors.primary))
-                        .border_style(Style::default().fg(app.theme_colors.border)),
-                )
-                .data(&cpu_data)
-                .style(Style::default().fg(app.theme_colors.success))
-                .max(20); // Max 100% * 0.2 = 20
-            f.render_widget(cpu_sparkline, chunks[0]);
-        } else {
-            // Show current usage as bar when no history
-            let current_cpu = (service.cpu_usage * 0.2) as u64;
-            let cpu_data = vec![current_cpu];
-            let cpu_sparkline = Sparkline::default()
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title(format!("CPU Usage ({}%)", service.cpu_usage as u32))
-                        .title_style(Style::default().fg(app.theme_colors.primary))
-                        .border_style(Style::default().fg(app.theme_colors.border)),
-                )
-                .data(&cpu_data)
-                .style(Style::default().fg(app.theme_colors.success))
-                .max(20);
-            f.render_widget(cpu_sparkline, chunks[0]);
-        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(with_query(app, windowed(app, format!("CPU Usage ({}%)", service.cpu_usage as u32)), &app.config.prometheus.service_queries.cpu_usage))
+            .title_style(Style::default().fg(app.theme_colors.primary))
+            .border_style(Style::default().fg(app.theme_colors.border));
+        render_graph(f, app, chunks[0], block, samples_or_current(Some(history), service.cpu_usage), 100.0, app.theme_colors.success);
     } else {
         let placeholder = Paragraph::new(format!("CPU: {:.1}% | Initializing...", service.cpu_usage))
             .style(Style::default().fg(app.theme_colors.text_muted))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("CPU Usage")
+                    .title(with_query(app, "CPU Usage", &app.config.prometheus.service_queries.cpu_usage))
                     .title_style(Style::default().fg(app.theme_colors.primary))
                     .border_style(Style::default().fg(app.theme_colors.border)),
             );
         f.render_widget(placeholder, chunks[0]);
     }
 
-    // Service Memory with improved visibility
-    let memory_data: Vec<u64> = (0..30).map(|i| {
-        let base = service.memory_usage;
-        let variation = (i as f64 * 0.1).sin() * 2.0;
-        ((base + variation).max(0.0).min(100.0) * 0.2) as u64 // Scale to 0-20 range
-    }).collect();
-
-    let memory_sparkline = Sparkline::default()
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(format!("Memory Usage ({}%)", service.memory_usage as u32))
-                .title_style(Style::default().fg(app.theme_colors.info))
-                .border_style(Style::default().fg(app.theme_colors.border)),
-        )
-        .data(&memory_data)
-        .style(Style::default().fg(app.theme_colors.info))
-        .max(20); // Max 100% * 0.2 = 20
-    f.render_widget(memory_sparkline, chunks[1]);
+    // Service memory history, or just the current usage until history builds up
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(with_query(app, windowed(app, format!("Memory Usage ({}%)", service.memory_usage as u32)), &app.config.prometheus.service_queries.memory_usage))
+        .title_style(Style::default().fg(app.theme_colors.info))
+        .border_style(Style::default().fg(app.theme_colors.border));
+    render_graph(f, app, chunks[1], block, samples_or_current(app.service_memory_history.get(service_name), service.memory_usage), 100.0, app.theme_colors.info);
 }
 
 fn render_service_logs(f: &mut Frame, app: &App, area: Rect) {
-    // Get selected service using the same sorting as the services table
-    let mut filtered_services: Vec<_> = app.services
-        .iter()
-        .filter(|(_name, service)| {
-            // Show all homelab services
-            service.namespace == "homelab"
-        })
-        .collect();
-
-    // Sort alphabetically by service name - same as services table
-    filtered_services.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
-
-    if filtered_services.is_empty() || app.selected_service_index >= filtered_services.len() {
+    // Selected service (same ordering as the services table), unless one is pinned
+    let Some(service_name) = app.detail_service_name() else {
         let placeholder = Paragraph::new("No services available")
             .style(Style::default().fg(app.theme_colors.text_muted))
             .block(
@@ -691,9 +1666,16 @@ fn render_service_logs(f: &mut Frame, app: &App, area: Rect) {
             );
         f.render_widget(placeholder, area);
         return;
-    }
+    };
+
+    let service_name = service_name.as_str();
+    let service = &app.services[service_name];
 
-    let (service_name, service) = &filtered_services[app.selected_service_index];
+    // Real events replace the simulated logs when an events query is configured
+    if app.config.prometheus.events_query.is_some() {
+        render_service_events(f, app, service, area);
+        return;
+    }
 
     // Create realistic log content based on service status
     let log_content = if service.status != "Running" {
@@ -771,11 +1753,73 @@ fn render_service_logs(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(logs_widget, area);
 }
 
+/// Recent Kubernetes events for the service's pod, warnings first
+fn render_service_events(f: &mut Frame, app: &App, service: &crate::mock_data::ServiceMetrics, area: Rect) {
+    let warnings = service.events.iter().filter(|event| event.is_warning()).count();
+    let lines: Vec<Line> = if service.events.is_empty() {
+        vec![Line::from(Span::styled("No recent events", Style::default().fg(app.theme_colors.text_muted)))]
+    } else {
+        service.events
+            .iter()
+            .map(|event| {
+                let (tag, color) = if event.is_warning() {
+                    ("WARN ", app.theme_colors.gauge_warning)
+                } else {
+                    ("INFO ", app.theme_colors.text_muted)
+                };
+                let mut spans = vec![
+                    Span::styled(tag, Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::styled(event.reason.as_str(), Style::default().fg(color)),
+                ];
+                if event.count > 1 {
+                    spans.push(Span::styled(format!(" ×{}", event.count), Style::default().fg(app.theme_colors.text_muted)));
+                }
+                if !event.message.is_empty() {
+                    spans.push(Span::raw(format!(" {}", event.message)));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    };
+
+    let border_color = if warnings > 0 { app.theme_colors.gauge_warning } else { app.theme_colors.border };
+    let events_widget = Paragraph::new(lines)
+        .style(Style::default().fg(app.theme_colors.foreground))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(if warnings > 0 { format!("Events ({} warning)", warnings) } else { "Events".to_string() })
+                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
+                .border_style(Style::default().fg(border_color)),
+        )
+        .wrap(ratatui::widgets::Wrap { trim: true });
+
+    f.render_widget(events_widget, area);
+}
+
 fn render_services_table(f: &mut Frame, app: &App, area: Rect) {
-    let header_cells = ["Service", "Namespace", "Status", "CPU", "Memory", "RPS", "Latency", "Error", "Replicas"]
+    // Namespace, Status, CPU, Memory, RPS, Latency, Error, Replicas, Age; the name column takes the rest
+    let columns = responsive_columns(area.width, 12, &[10, 8, 6, 7, 7, 8, 6, 8, 5]);
+    let column_count = columns.len();
+
+    let sorted_column = match app.service_sort {
+        ServiceSortKey::Name => "Service",
+        ServiceSortKey::Status => "Status",
+        ServiceSortKey::Cpu => "CPU",
+        ServiceSortKey::Memory => "Memory",
+        ServiceSortKey::Rps => "RPS",
+        ServiceSortKey::Latency => "Latency",
+        ServiceSortKey::ErrorRate => "Error",
+    };
+    let arrow = if app.service_sort_desc { "▼" } else { "▲" };
+    let default_order = app.service_sort == ServiceSortKey::Name && !app.service_sort_desc;
+    let header_cells = ["Service", "Namespace", "Status", "CPU", "Memory", "RPS", "Latency", "Error", "Replicas", "Age"]
         .iter()
+        .take(column_count)
         .map(|h| {
-            Cell::from(*h)
+            // No space before the arrow; the metric columns are tight
+            let label = if *h == sorted_column && !default_order { format!("{}{}", h, arrow) } else { h.to_string() };
+            Cell::from(label)
                 .style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
         });
 
@@ -783,23 +1827,15 @@ fn render_services_table(f: &mut Frame, app: &App, area: Rect) {
         .style(Style::default().bg(app.theme_colors.border))
         .height(1);
 
-    // Sort services alphabetically by name for consistent ordering
-    let mut filtered_services: Vec<_> = app.services
-        .iter()
-        .filter(|(_name, service)| {
-            // Show all homelab services
-            service.namespace == "homelab"
-        })
-        .collect();
-
-    // Sort alphabetically by service name but maintain selection stability
-    filtered_services.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+    // Same filtered, sorted list that navigation walks
+    let service_names = app.filtered_service_names();
+    let (start, end) = visible_rows(service_names.len(), table_capacity(area), app.selected_service_index);
+    app.services_table_area.set(area);
+    app.services_table_offset.set(start);
 
-    // Convert to indexed format for selection tracking
-    let indexed_services: Vec<_> = filtered_services.into_iter().enumerate().collect();
-
-    let rows = indexed_services.iter().map(|(i, (name, service))| {
-        let is_selected = *i == app.selected_service_index;
+    let rows = service_names.iter().enumerate().skip(start).take(end - start).map(|(i, name)| {
+        let service = &app.services[name];
+        let is_selected = i == app.selected_service_index;
         let is_active_panel = app.active_panel == ActivePanel::Services;
 
         let cpu_color = if service.cpu_usage > 50.0 { app.theme_colors.gauge_danger }
@@ -831,35 +1867,34 @@ fn render_services_table(f: &mut Frame, app: &App, area: Rect) {
         let cells = vec![
             Cell::from(if is_selected && is_active_panel { format!("► {}", name) } else { name.to_string() }),
             Cell::from(service.namespace.clone()),
-            Cell::from(service.status.clone()).style(Style::default().fg(status_color)),
-            Cell::from(format!("{:.1}%", service.cpu_usage)).style(Style::default().fg(cpu_color)),
-            Cell::from(format!("{:.1}%", service.memory_usage)).style(Style::default().fg(mem_color)),
-            Cell::from(format!("{:.1}", service.requests_per_sec)).style(Style::default().fg(rps_color)),
-            Cell::from(format!("{:.0}ms", service.response_time)).style(Style::default().fg(latency_color)),
-            Cell::from(format!("{:.1}%", service.error_rate)).style(Style::default().fg(error_color)),
+            Cell::from(service.status.clone()).style(metric_style(app, service.stale_metrics.contains("status"), status_color)),
+            Cell::from(localized(app, format!("{:.1}%", service.cpu_usage))).style(metric_style(app, service.stale_metrics.contains("cpu"), cpu_color)),
+            Cell::from(localized(app, format!("{:.1}%", service.memory_usage))).style(metric_style(app, false, mem_color)),
+            Cell::from(crate::format::count(service.requests_per_sec, &app.config.ui)).style(metric_style(app, false, rps_color)),
+            Cell::from(localized(app, format!("{:.0}ms", service.response_time))).style(metric_style(app, false, latency_color)),
+            Cell::from(localized(app, format!("{:.1}%", service.error_rate))).style(metric_style(app, false, error_color)),
             Cell::from(format!("{}/{}", service.ready_replicas, service.replicas))
                 .style(Style::default().fg(replica_status)),
+            age_cell(app, service),
         ];
 
-        let style = if is_selected && is_active_panel {
-            Style::default().bg(app.theme_colors.highlight).add_modifier(Modifier::BOLD)
-        } else {
-            Style::default()
-        };
-
-        Row::new(cells).style(style)
+        let is_hovered = app.hovered_row == Some((ActivePanel::Services, i));
+        let cells = cells.into_iter().take(column_count).collect();
+        styled_row(app, cells, is_selected && is_active_panel, is_hovered)
     });
 
-    let table = Table::new(rows, [Constraint::Min(12), Constraint::Min(10), Constraint::Min(8),
-                                   Constraint::Min(5), Constraint::Min(6), Constraint::Min(6),
-                                   Constraint::Min(7), Constraint::Min(6), Constraint::Min(8)])
+    let table = Table::new(rows, columns)
         .header(header)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Services")
+                .title(format!("Services{}", pagination_label(start, end, service_names.len())))
+                .title(health_summary_line(app, &service_names).right_aligned())
                 .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
-                .border_style(if app.active_panel == ActivePanel::Services {
+                .border_style(if app.is_flashing(ActivePanel::Services) {
+                    // Values just changed
+                    Style::default().fg(app.theme_colors.info).add_modifier(Modifier::BOLD)
+                } else if app.active_panel == ActivePanel::Services {
                     Style::default().fg(app.theme_colors.info)
                 } else {
                     Style::default().fg(app.theme_colors.border)
@@ -870,252 +1905,272 @@ fn render_services_table(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(table, area);
 }
 
-fn render_service_details(f: &mut Frame, app: &App, area: Rect) {
-    let service_names: Vec<String> = app.services.keys().cloned().collect();
-    if service_names.is_empty() || app.selected_service_index >= service_names.len() {
-        return;
-    }
+/// Pods younger than this were likely just restarted or rescheduled
+const YOUNG_POD_SECS: u64 = 3600;
 
-    let service_name = &service_names[app.selected_service_index];
-    let service = &app.services[service_name];
+/// "Cert: expires in 12d", in warning colors within `cert_warning_days`
+fn cert_line<'a>(app: &App, expires_at: u64) -> Line<'a> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (text, style) = if expires_at <= now {
+        (
+            format!("expired {} ago", crate::format::format_age(now - expires_at)),
+            Style::default().fg(app.theme_colors.error).add_modifier(Modifier::BOLD),
+        )
+    } else {
+        let remaining = expires_at - now;
+        let warning_secs = app.config.health_checks.cert_warning_days.unwrap_or(0) * 86_400;
+        let color = if remaining < warning_secs { app.theme_colors.warning } else { app.theme_colors.foreground };
+        (format!("expires in {}", crate::format::format_age(remaining)), Style::default().fg(color))
+    };
+    Line::from(vec![
+        Span::styled("Cert: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
+        Span::styled(text, style),
+    ])
+}
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(4), Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-        .split(area);
+/// Warning color while the pod is young
+fn age_style(app: &App, age: u64) -> Style {
+    if age < YOUNG_POD_SECS {
+        Style::default().fg(app.theme_colors.gauge_warning).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(app.theme_colors.foreground)
+    }
+}
 
-    // Selected service details
-    render_selected_service_details(f, app, service_name, service, chunks[0]);
+fn age_cell<'a>(app: &App, service: &crate::mock_data::ServiceMetrics) -> Cell<'a> {
+    match service.age {
+        0 => Cell::from("-").style(Style::default().fg(app.theme_colors.text_muted)),
+        age => Cell::from(crate::format::format_age(age)).style(age_style(app, age)),
+    }
+}
 
-    // Pod metrics
-    render_service_metrics(f, app, service, chunks[1]);
+/// Running image, highlighted with the expected one when they differ
+fn image_line<'a>(app: &App, service: &'a crate::mock_data::ServiceMetrics) -> Line<'a> {
+    let mut spans = vec![Span::styled("Image: ", Style::default().fg(app.theme_colors.text_muted))];
+    match app.unexpected_image(service) {
+        Some(expected) => {
+            spans.push(Span::styled(service.image.as_str(), Style::default().fg(app.theme_colors.gauge_warning).add_modifier(Modifier::BOLD)));
+            spans.push(Span::styled(format!(" (expected {})", expected), Style::default().fg(app.theme_colors.gauge_warning)));
+        }
+        None if service.image == "unknown" => spans.push(Span::styled("unknown", Style::default().fg(app.theme_colors.text_muted))),
+        None => spans.push(Span::styled(service.image.as_str(), Style::default().fg(app.theme_colors.foreground))),
+    }
+    Line::from(spans)
+}
 
-    // Health metrics
-    render_service_health(f, app, service_name, service, chunks[2]);
+/// The service's note, or the draft while it is being edited
+fn note_line<'a>(app: &'a App, service: &str) -> Option<Line<'a>> {
+    let label = Span::styled("Note: ", Style::default().fg(app.theme_colors.text_muted));
+    match &app.note_editing {
+        Some((editing, draft)) if editing == service => Some(Line::from(vec![
+            label,
+            Span::styled(format!("{}▏", draft), Style::default().fg(app.theme_colors.primary)),
+            Span::styled(" (Enter saves, Esc cancels)", Style::default().fg(app.theme_colors.text_muted)),
+        ])),
+        _ => app.notes.get(service).map(|note| {
+            Line::from(vec![label, Span::styled(note, Style::default().fg(app.theme_colors.foreground).add_modifier(Modifier::ITALIC))])
+        }),
+    }
 }
 
-fn render_service_details_extended(f: &mut Frame, app: &App, service_name: &str, service: &crate::mock_data::ServiceMetrics, area: Rect) {
-    let details = vec![
-        Line::from(vec![
-            Span::styled("Service: ", Style::default().fg(app.theme_colors.primary)),
-            Span::styled(service_name, Style::default().add_modifier(Modifier::BOLD).fg(app.theme_colors.foreground)),
-        ]),
-        Line::from(vec![
-            Span::styled("Namespace: ", Style::default().fg(app.theme_colors.primary)),
-            Span::styled(&service.namespace, Style::default().fg(app.theme_colors.foreground)),
-        ]),
-        Line::from(vec![
-            Span::styled("Status: ", Style::default().fg(app.theme_colors.primary)),
-            Span::styled(
-                &service.status,
-                if service.status == "Running" {
-                    Style::default().fg(app.theme_colors.success)
-                } else {
-                    Style::default().fg(app.theme_colors.error)
-                },
-            ),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("CPU: ", Style::default().fg(app.theme_colors.primary)),
-            Span::styled(format!("{:.1}%", service.cpu_usage), Style::default().fg(app.theme_colors.foreground)),
-        ]),
-        Line::from(vec![
-            Span::styled("Memory: ", Style::default().fg(app.theme_colors.primary)),
-            Span::styled(format!("{:.1}%", service.memory_usage), Style::default().fg(app.theme_colors.foreground)),
-        ]),
+/// Cells of the health summary bar in the services table title
+const HEALTH_BAR_WIDTH: usize = 12;
+
+/// Stacked bar of the listed services' health (healthy, starting, degraded,
+/// unhealthy, unknown) with the count of each
+fn health_summary_line<'a>(app: &App, service_names: &[String]) -> Line<'a> {
+    let statuses = [
+        ("Healthy", app.theme_colors.success),
+        ("Starting", app.theme_colors.secondary),
+        ("Degraded", app.theme_colors.gauge_warning),
+        ("Unhealthy", app.theme_colors.gauge_danger),
     ];
+    let mut counts = [0usize; 5];
+    for name in service_names {
+        let status = app.health_status(&app.services[name]);
+        let index = statuses.iter().position(|(label, _)| *label == status).unwrap_or(statuses.len());
+        counts[index] += 1;
+    }
+    let colors: Vec<Color> = statuses.iter().map(|(_, color)| *color).chain([app.theme_colors.text_muted]).collect();
 
-    let details_widget = Paragraph::new(details)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Service Details")
-                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
-                .border_style(Style::default().fg(app.theme_colors.border)),
-        )
-        .wrap(ratatui::widgets::Wrap { trim: true });
-
-    f.render_widget(details_widget, area);
+    let mut spans: Vec<Span> = segment_widths(&counts, HEALTH_BAR_WIDTH)
+        .into_iter()
+        .zip(&colors)
+        .filter(|(width, _)| *width > 0)
+        .map(|(width, color)| Span::styled("█".repeat(width), Style::default().fg(*color)))
+        .collect();
+    for (count, color) in counts.iter().zip(&colors).filter(|(count, _)| **count > 0) {
+        spans.push(Span::styled(format!(" {}", count), Style::default().fg(*color).add_modifier(Modifier::BOLD)));
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
 }
 
-fn render_selected_service_details(f: &mut Frame, app: &App, service_name: &str, service: &crate::mock_data::ServiceMetrics, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
-        .split(area);
-
-    // Service summary
-    let details_text = format!("{} | Namespace: {} | Status: {} | Replicas: {}/{} | CPU: {:.1}% | Memory: {:.1}%",
-        service_name,
-        service.namespace,
-        service.status,
-        service.ready_replicas,
-        service.replicas,
-        service.cpu_usage,
-        service.memory_usage
-    );
-
-    let details = Paragraph::new(details_text)
-        .style(Style::default().fg(app.theme_colors.foreground))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Selected Service")
-                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
-                .border_style(Style::default().fg(app.theme_colors.border)),
-        );
+/// Split `width` cells between `counts` in proportion, giving every nonzero
+/// count at least one cell while there's room
+fn segment_widths(counts: &[usize], width: usize) -> Vec<usize> {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return vec![0; counts.len()];
+    }
+    let mut widths: Vec<usize> = counts
+        .iter()
+        .map(|&count| if count == 0 { 0 } else { ((count * width + total / 2) / total).max(1) })
+        .collect();
+    while widths.iter().sum::<usize>() > width {
+        let Some(widest) = (0..widths.len()).filter(|&i| widths[i] > 1).max_by_key(|&i| widths[i]) else {
+            break;
+        };
+        widths[widest] -= 1;
+    }
+    while widths.iter().sum::<usize>() < width {
+        let largest = (0..counts.len()).max_by_key(|&i| counts[i]).unwrap_or(0);
+        widths[largest] += 1;
+    }
+    widths
+}
 
-    f.render_widget(details, chunks[0]);
+/// Graph title with the zoomed-out time window, if any ('+'/'-')
+fn windowed(app: &App, title: String) -> String {
+    match app.graph_window_label() {
+        Some(window) => format!("{} · {}", title, window),
+        None => title,
+    }
+}
 
-    // Error logs or service status
-    let log_content = if service.status != "Running" {
-        vec![
-            Line::from(Span::styled("Service Status: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))),
-            Line::from(Span::styled(format!("  • Status: {}", service.status),
-                if service.status == "Running" { Style::default().fg(app.theme_colors.success) }
-                else { Style::default().fg(app.theme_colors.warning) })),
-            Line::from(Span::styled(format!("  • Ready Replicas: {}/{}", service.ready_replicas, service.replicas),
-                if service.ready_replicas == service.replicas { Style::default().fg(app.theme_colors.success) }
-                else { Style::default().fg(app.theme_colors.warning) })),
-            Line::from(Span::styled("  • Health Check: Failed", Style::default().fg(app.theme_colors.gauge_danger))),
-            Line::from(vec![]),
-            Line::from(Span::styled("Recent Errors:", Style::default().fg(app.theme_colors.gauge_danger).add_modifier(Modifier::BOLD))),
-            Line::from(Span::styled("  [ERROR] Connection timeout to database", Style::default().fg(app.theme_colors.gauge_danger))),
-            Line::from(Span::styled("  [WARN]  High memory usage detected", Style::default().fg(app.theme_colors.gauge_warning))),
-            Line::from(Span::styled("  [ERROR] Failed to start pod: CrashLoopBackOff", Style::default().fg(app.theme_colors.gauge_danger))),
-        ]
+/// Detail panel title, marked when the panel is pinned to `item`
+fn pinned_title(app: &App, title: &str, item: &str) -> String {
+    if app.pinned_detail.as_deref() == Some(item) {
+        format!("{} 📌 {}", title, item)
     } else {
-        vec![
-            Line::from(Span::styled("Service Status: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))),
-            Line::from(Span::styled(format!("  • Status: {}", service.status), Style::default().fg(app.theme_colors.success))),
-            Line::from(Span::styled(format!("  • Ready Replicas: {}/{}", service.ready_replicas, service.replicas), Style::default().fg(app.theme_colors.success))),
-            Line::from(Span::styled("  • Health Check: OK", Style::default().fg(app.theme_colors.success))),
-            Line::from(vec![]),
-            Line::from(Span::styled("Recent Logs:", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))),
-            Line::from(Span::styled("  [INFO] Service started successfully", Style::default().fg(app.theme_colors.text_muted))),
-            Line::from(Span::styled("  [INFO] Health check passed", Style::default().fg(app.theme_colors.text_muted))),
-            Line::from(Span::styled("  [INFO] Processing requests normally", Style::default().fg(app.theme_colors.text_muted))),
-        ]
-    };
-
-    let logs_widget = Paragraph::new(log_content)
-        .style(Style::default().fg(app.theme_colors.foreground))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Service Logs & Status")
-                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
-                .border_style(Style::default().fg(app.theme_colors.border)),
-        )
-        .wrap(ratatui::widgets::Wrap { trim: true });
-
-    f.render_widget(logs_widget, chunks[1]);
+        title.to_string()
+    }
 }
 
-fn render_service_metrics(f: &mut Frame, app: &App, service: &crate::mock_data::ServiceMetrics, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(33), Constraint::Percentage(33), Constraint::Percentage(34)].as_ref())
-        .split(area);
-
-    // CPU Usage Gauge (real data)
-    let cpu_gauge = Gauge::default()
-        .block(Block::default().borders(Borders::ALL).title("CPU Usage").border_style(Style::default().fg(app.theme_colors.border)))
-        .gauge_style(
-            Style::default()
-                .fg(if service.cpu_usage > 80.0 { app.theme_colors.gauge_danger } else if service.cpu_usage > 60.0 { app.theme_colors.gauge_warning } else { app.theme_colors.gauge_good })
-                .bg(app.theme_colors.background)
-                .add_modifier(Modifier::BOLD),
-        )
-        .label(format!("{:.1}%", service.cpu_usage))
-        .ratio(service.cpu_usage / 100.0);
-    f.render_widget(cpu_gauge, chunks[0]);
-
-    // Memory Usage Gauge
-    let memory_gauge = Gauge::default()
-        .block(Block::default().borders(Borders::ALL).title("Memory Usage").border_style(Style::default().fg(app.theme_colors.border)))
-        .gauge_style(
-            Style::default()
-                .fg(if service.memory_usage > 80.0 { app.theme_colors.gauge_danger } else if service.memory_usage > 60.0 { app.theme_colors.gauge_warning } else { app.theme_colors.gauge_good })
-                .bg(app.theme_colors.background)
-                .add_modifier(Modifier::BOLD),
-        )
-        .label(format!("{:.1}%", service.memory_usage))
-        .ratio(service.memory_usage / 100.0);
-    f.render_widget(memory_gauge, chunks[1]);
-
-    // Service Status
-    let status_text = vec![
-        Line::from(vec![
-            Span::styled("Status: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
-            Span::styled(
-                &service.status,
-                if service.status == "Running" {
-                    Style::default().fg(app.theme_colors.success).add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(app.theme_colors.error).add_modifier(Modifier::BOLD)
-                },
-            ),
-        ]),
-        Line::from(vec![
-            Span::styled("Replicas: ", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD)),
-            Span::raw(format!("{}/{}", service.ready_replicas, service.replicas)),
-        ]),
-    ];
-
-    let status_paragraph = Paragraph::new(status_text)
-        .style(Style::default().fg(app.theme_colors.foreground))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Service Info")
-                .title_style(Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))
-                .border_style(Style::default().fg(app.theme_colors.border)),
-        );
-    f.render_widget(status_paragraph, chunks[2]);
+/// "vs <name> (<time>): CPU +1.2% ..." deltas against the saved baseline
+fn baseline_line<'a>(app: &App, baseline: &crate::baseline::Baseline, deltas: Vec<(&str, f64, &str)>) -> Line<'a> {
+    let mut spans = vec![Span::styled(
+        format!("vs {} ({}): ", baseline.name, crate::html_export::format_utc(baseline.created_at)),
+        Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD),
+    )];
+    if deltas.is_empty() {
+        spans.push(Span::styled("not in baseline", Style::default().fg(app.theme_colors.text_muted)));
+    }
+    for (index, (label, delta, unit)) in deltas.into_iter().enumerate() {
+        let separator = if index == 0 { "" } else { " " };
+        spans.push(Span::styled(format!("{}{} ", separator, label), Style::default().fg(app.theme_colors.text_muted)));
+        spans.push(Span::styled(format!("{:+.1}{}", delta, unit), Style::default().fg(app.theme_colors.foreground)));
+    }
+    Line::from(spans)
 }
 
-fn render_service_health(f: &mut Frame, app: &App, service_name: &str, service: &crate::mock_data::ServiceMetrics, area: Rect) {
+fn render_service_health(f: &mut Frame, app: &App, _service_name: &str, service: &crate::mock_data::ServiceMetrics, area: Rect) {
     // Get health status color based on health_status
-    let (health_color, health_icon) = match service.health_status.as_str() {
+    let health_status = app.health_status(service);
+    let (health_color, health_icon) = match health_status {
         "Healthy" => (app.theme_colors.success, "✅"),
+        "Starting" => (app.theme_colors.secondary, "⏳"),
         "Degraded" => (app.theme_colors.gauge_warning, "⚠️"),
         "Unhealthy" => (app.theme_colors.gauge_danger, "❌"),
         _ => (app.theme_colors.text_muted, "❓"),
     };
 
-    let health_content = vec![
+    let mut health_content = vec![
         Line::from(Span::styled("Health Probe", Style::default().fg(app.theme_colors.primary).add_modifier(Modifier::BOLD))),
         Line::from(vec![]),
         Line::from(vec![
             Span::styled("Status: ", Style::default().fg(app.theme_colors.text_muted)),
-            Span::styled(format!("{} {}", health_icon, service.health_status), Style::default().fg(health_color).add_modifier(Modifier::BOLD))
+            Span::styled(format!("{} {}", health_icon, health_status), Style::default().fg(health_color).add_modifier(Modifier::BOLD))
         ]),
         Line::from(vec![
             Span::styled("Response: ", Style::default().fg(app.theme_colors.text_muted)),
-            Span::styled(format!("{:.1}ms", service.health_response_time), Style::default().fg(app.theme_colors.foreground))
+            Span::styled(localized(app, format!("{:.1}ms", service.health_response_time)), Style::default().fg(app.theme_colors.foreground))
         ]),
         Line::from(vec![
             Span::styled("Failures: ", Style::default().fg(app.theme_colors.text_muted)),
             Span::styled(format!("{}", service.consecutive_failures),
                 if service.consecutive_failures > 0 { app.theme_colors.gauge_danger } else { app.theme_colors.success })
         ]),
+        Line::from(vec![
+            Span::styled("Age: ", Style::default().fg(app.theme_colors.text_muted)),
+            match service.age {
+                0 => Span::styled("unknown", Style::default().fg(app.theme_colors.text_muted)),
+                age => Span::styled(crate::format::format_age(age), age_style(app, age)),
+            },
+        ]),
+        Line::from(vec![
+            Span::styled("Uptime: ", Style::default().fg(app.theme_colors.text_muted)),
+            Span::raw(crate::format::format_uptime(service.uptime)),
+        ]),
+        image_line(app, service),
+    ];
+    if let Some(expires_at) = service.cert_expires_at {
+        health_content.push(cert_line(app, expires_at));
+    }
+    health_content.extend(note_line(app, &service.name));
+    health_content.extend([
         Line::from(vec![]),
         Line::from(Span::styled(format!("Endpoint: {}", service.health_endpoint),
             Style::default().fg(app.theme_colors.text_muted).add_modifier(Modifier::ITALIC))),
-    ];
+    ]);
+    if let Some(baseline) = app.active_baseline() {
+        health_content.push(match baseline.services.get(&service.name) {
+            Some(then) => baseline_line(app, baseline, vec![
+                ("CPU", service.cpu_usage - then.cpu_usage, "%"),
+                ("Mem", service.memory_usage - then.memory_usage, "%"),
+                ("RPS", service.requests_per_sec - then.requests_per_sec, ""),
+                ("Latency", service.response_time - then.response_time, "ms"),
+                ("Errors", service.error_rate - then.error_rate, "%"),
+            ]),
+            None => baseline_line(app, baseline, Vec::new()),
+        });
+    }
 
     let health_widget = Paragraph::new(health_content)
         .style(Style::default().fg(app.theme_colors.foreground))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Health Check")
+                .title(pinned_title(app, "Health Check", &service.name))
                 .title_style(Style::default().fg(health_color).add_modifier(Modifier::BOLD))
                 .border_style(Style::default().fg(health_color)),
         )
         .wrap(ratatui::widgets::Wrap { trim: true });
 
     f.render_widget(health_widget, area);
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    /// Render the full UI for a mock-mode app into a plain-text grid
+    fn render_to_string(app: &App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        buffer_text(terminal.backend().buffer())
+    }
+
+    #[test]
+    fn segment_widths_fill_the_bar_in_proportion() {
+        assert_eq!(segment_widths(&[5, 0, 1, 1, 0], 12), vec![8, 0, 2, 2, 0]);
+        assert_eq!(segment_widths(&[100, 0, 0, 1, 0], 12), vec![11, 0, 0, 1, 0]);
+        assert_eq!(segment_widths(&[1, 1], 12), vec![6, 6]);
+        assert_eq!(segment_widths(&[0, 0], 12), vec![0, 0]);
+    }
+
+    #[test]
+    fn main_layout_snapshot() {
+        let app = App::new_mock(Config::default());
+        let rendered = render_to_string(&app, 160, 48);
+
+        // Regenerate with UPDATE_SNAPSHOTS=1 cargo test
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/snapshots/main_layout.txt");
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::write(path, &rendered).unwrap();
+        }
+        let expected = std::fs::read_to_string(path).expect("missing snapshot, run with UPDATE_SNAPSHOTS=1");
+        assert_eq!(rendered, expected.trim_end_matches('\n'));
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use crate::app::{ActivePanel, DisplayMode, FilterState, NodeSortKey, ServiceSortKey};
+use crate::config::{Config, CurrentTab};
+
+/// Selection, sort and filters of one cluster, restored on the next launch
+/// when `ui.remember_view` is set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewState {
+    pub current_tab: CurrentTab,
+    pub active_panel: ActivePanel,
+    pub selected_node: Option<String>,
+    pub selected_service: Option<String>,
+    pub node_sort: NodeSortKey,
+    pub node_sort_desc: bool,
+    #[serde(default = "default_service_sort")]
+    pub service_sort: ServiceSortKey,
+    #[serde(default)]
+    pub service_sort_desc: bool,
+    pub filter: FilterState,
+    pub display_mode: DisplayMode,
+    pub selected_items: Vec<String>,
+}
+
+/// State saved before services could be sorted keeps the name order
+fn default_service_sort() -> ServiceSortKey {
+    ServiceSortKey::Name
+}
+
+impl ViewState {
+    /// `~/.monitorium/state/<cluster_name>.json`, with anything but letters,
+    /// digits, `-` and `_` in the name replaced by `_`
+    pub fn path(cluster_name: &str) -> anyhow::Result<PathBuf> {
+        let file_name: String = cluster_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        Ok(Config::config_dir()?.join("state").join(format!("{}.json", file_name)))
+    }
+
+    /// The cluster's saved state, or `None` if it was never saved
+    pub fn load(cluster_name: &str) -> anyhow::Result<Option<Self>> {
+        let path = Self::path(cluster_name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read view state: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .with_context(|| format!("Failed to parse view state: {}", path.display()))
+    }
+
+    pub fn save(&self, cluster_name: &str) -> anyhow::Result<PathBuf> {
+        let path = Self::path(cluster_name)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create state directory: {}", dir.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize view state")?;
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write view state: {}", path.display()))?;
+        Ok(path)
+    }
+}